@@ -3,6 +3,24 @@ extern crate sudoku;
 use std::io::{self, BufRead, Write};
 use std::process;
 
+use sudoku::SolveError;
+
+// Exit codes. When several lines fail to solve, the process exits with the code of the first failure.
+const EXIT_INVALID_BOARD: i32 = 1;
+const EXIT_NO_SOLUTION: i32 = 2;
+const EXIT_CONTRADICTORY_GIVENS: i32 = 3;
+const EXIT_MULTIPLE_SOLUTIONS: i32 = 4;
+const EXIT_ABORTED: i32 = 5;
+
+fn exit_code(err: &SolveError) -> i32 {
+    match *err {
+        SolveError::NoSolution => EXIT_NO_SOLUTION,
+        SolveError::ContradictoryGivens(_) => EXIT_CONTRADICTORY_GIVENS,
+        SolveError::MultipleSolutions => EXIT_MULTIPLE_SOLUTIONS,
+        SolveError::Aborted => EXIT_ABORTED
+    }
+}
+
 fn main() {
     let stdin = io::stdin();
     let mut stdin_lock = stdin.lock();
@@ -10,27 +28,39 @@ fn main() {
     let stdout = io::stdout();
     let mut stdout_lock = stdout.lock();
 
+    // Builds the exact cover matrix once, rather than once per line.
+    let mut solver = sudoku::MatrixSolver::new();
+    let mut status = 0;
+
     let mut line = String::with_capacity(9*9*2+1);
     while let Ok(bytes_read) = stdin_lock.read_line(&mut line) {
-        if (bytes_read == 0) {
+        if bytes_read == 0 {
             break;
         }
-        line.trim();
-        let board = sudoku::Board::from_singleline_str(&line);
-        if let Err(err) = board {
-            eprintln!("Invalid board: {:?}", err);
-            process::exit(1);
-        }
-        let mut board = board.unwrap();
-        let solved = board.solve();
-        // writeln!(stdout_lock, "{}", board);
-        if let Ok(_) = solved {
-            board.to_line(&mut stdout_lock);
-            writeln!(stdout_lock);
-            stdout_lock.flush();
-        } else {
-            eprintln!("No solution");
+        let mut board = match sudoku::Board::from_singleline_str(&line) {
+            Ok(board) => board,
+            Err(err) => {
+                eprintln!("Invalid board: {:?}", err);
+                process::exit(EXIT_INVALID_BOARD);
+            }
+        };
+        // Puzzles with more than one completion are reported rather than solved arbitrarily.
+        match solver.solve_unique(&mut board) {
+            Ok(()) => {
+                board.to_line(&mut stdout_lock);
+                writeln!(stdout_lock)
+                    .and_then(|_| stdout_lock.flush())
+                    .expect("Failed to write solution to stdout");
+            },
+            Err(err) => {
+                eprintln!("Unsolvable board: {}", err);
+                if status == 0 {
+                    status = exit_code(&err);
+                }
+            }
         }
         line.clear();
     }
+
+    process::exit(status);
 }
@@ -1,10 +1,15 @@
 use std::fmt::{Display, Write, Formatter, Debug};
 
 use matrix::Matrix;
-
-// A possible choice in a Sudoku puzzle. A single Possibility represents the choice
-// to place a certain number at a certain position (row and column) within the board.
-#[derive(Debug, Clone, Copy, Default, Eq, PartialEq)]
+use solvers::MatrixSolver;
+use candidates::Candidates;
+use logic::{Hint, LogicalSolver, Trace};
+use rating::{Rater, Rating};
+use {Solve, SolveError};
+
+/// A possible choice in a Sudoku puzzle. A single Possibility represents the choice
+/// to place a certain number at a certain position (row and column) within the board.
+#[derive(Debug, Clone, Copy, Default, Eq, PartialEq, Ord, PartialOrd, Hash)]
 pub struct Possibility {
     // [0, 9)
     row: usize,
@@ -22,6 +27,19 @@ pub enum Constraint {
     Box
 }
 
+/// Two entries which repeat the same number within a row, column, or box.
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub struct Conflict {
+    /// (row, column) of the earlier cell in row-major order.
+    pub first: (usize, usize),
+    /// (row, column) of the later cell in row-major order.
+    pub second: (usize, usize),
+    /// The number both cells contain.
+    pub number: usize,
+    /// The kind of unit both cells are in. Never `Constraint::Cell`.
+    pub constraint: Constraint
+}
+
 /// Describes the state of a Sudoku puzzle board.
 #[derive(Clone)]
 pub struct Board {
@@ -31,10 +49,10 @@ pub struct Board {
 }
 
 impl Possibility {
-    fn new(row: usize, column: usize, number: usize) -> Possibility {
+    pub fn new(row: usize, column: usize, number: usize) -> Possibility {
         assert!(row < 9);
         assert!(column < 9);
-        assert!(number >= 1 && number <= 9);
+        assert!((1..=9).contains(&number));
         Possibility {
             row, column, number
         }
@@ -49,7 +67,19 @@ impl Possibility {
         }
     }
 
-    fn get_matrix_row(&self) -> usize {
+    pub fn row(&self) -> usize {
+        self.row
+    }
+
+    pub fn column(&self) -> usize {
+        self.column
+    }
+
+    pub fn number(&self) -> usize {
+        self.number
+    }
+
+    pub fn get_matrix_row(&self) -> usize {
         self.row * (9 * 9) + self.column * 9 + self.number - 1
     }
 
@@ -59,7 +89,7 @@ impl Possibility {
                 self.column + self.row * 9
             },
             Constraint::Row => {
-                81 * 1 + self.row * 9 + self.number - 1
+                81 + self.row * 9 + self.number - 1
             },
             Constraint::Column => {
                 81 * 2 + self.column * 9 + self.number - 1
@@ -79,11 +109,12 @@ impl Board {
         }
     }
 
+    // Write errors are ignored, as there is no way to report them to the caller.
     pub fn to_line<W: ::std::io::Write>(&self, to: &mut W) {
         for c in self.entries[0..9*9-1].iter() {
-            write!(to, "{} ", c);
+            let _ = write!(to, "{} ", c);
         }
-        write!(to, "{}", self.entries[9*9-1]);
+        let _ = write!(to, "{}", self.entries[9*9-1]);
     }
 
     pub fn get_entry(&self, row: usize, column: usize) -> Option<usize> {
@@ -93,44 +124,179 @@ impl Board {
         if entry == 0 { None } else { Some(entry) }
     }
 
-    pub fn solve(&mut self) -> Result<(), ()> {
-        let mut matrix = Matrix::new(9 * 9 * 9, 9 * 9 * 4, 9 * 9 * 9 * 4);
-        // Initializes the exact cover matrix and removes entries corresponding to knowns.
-        self.init_matrix(&mut matrix);
-        // Note that these solution_rows do not include the givens, but that's ok because
-        // the board already has the givens filled in.
-        let solution_rows = matrix.solve()?;
-
-        // Convert solution rows to Sudoku possibilities and record in the board.
-        for matrix_row in solution_rows {
-            let possibility = Possibility::from_matrix_row(matrix_row);
-            self.entries[possibility.column + possibility.row * 9] = possibility.number;
+    /// Places a number in [1, 9] at the given position, replacing any existing entry.
+    pub fn set_entry(&mut self, row: usize, column: usize, number: usize) {
+        assert!(row < 9);
+        assert!(column < 9);
+        assert!((1..=9).contains(&number));
+        self.entries[column + row * 9] = number;
+    }
+
+    /// Removes any entry at the given position, making it unknown.
+    pub fn clear_entry(&mut self, row: usize, column: usize) {
+        assert!(row < 9);
+        assert!(column < 9);
+        self.entries[column + row * 9] = 0;
+    }
+
+    /// Iterates over every cell in row-major order, yielding (row, column, entry) triples.
+    pub fn cells<'a>(&'a self) -> impl Iterator<Item = (usize, usize, Option<usize>)> + 'a {
+        (0..9 * 9).map(move |i| (i / 9, i % 9, self.get_entry(i / 9, i % 9)))
+    }
+
+    /// Iterates over the cells of a row from left to right, yielding (row, column, entry) triples.
+    pub fn row_cells<'a>(&'a self, row: usize) -> impl Iterator<Item = (usize, usize, Option<usize>)> + 'a {
+        self.unit_cells(Constraint::Row, row)
+    }
+
+    /// Iterates over the cells of a column from top to bottom, yielding (row, column, entry) triples.
+    pub fn column_cells<'a>(&'a self, column: usize) -> impl Iterator<Item = (usize, usize, Option<usize>)> + 'a {
+        self.unit_cells(Constraint::Column, column)
+    }
+
+    /// Iterates over the cells of a box in row-major order, yielding (row, column, entry) triples.
+    /// Boxes are numbered in row-major order, so box 0 is the top left box and box 8 the bottom right.
+    pub fn box_cells<'a>(&'a self, box_: usize) -> impl Iterator<Item = (usize, usize, Option<usize>)> + 'a {
+        self.unit_cells(Constraint::Box, box_)
+    }
+
+    fn unit_cells<'a>(&'a self, constraint: Constraint, index: usize) -> impl Iterator<Item = (usize, usize, Option<usize>)> + 'a {
+        let cells = unit_cells(constraint, index);
+        (0..9).map(move |i| (cells[i].0, cells[i].1, self.get_entry(cells[i].0, cells[i].1)))
+    }
+
+    /// Solves the board in place using dancing links over the generic exact cover `Matrix`.
+    pub fn solve(&mut self) -> Result<(), SolveError> {
+        self.solve_with(&mut MatrixSolver::new())
+    }
+
+    /// Solves the board in place using the given solver backend.
+    pub fn solve_with<S: Solve + ?Sized>(&mut self, solver: &mut S) -> Result<(), SolveError> {
+        solver.solve(self)
+    }
+
+    /// Solves the board in place, failing with `SolveError::MultipleSolutions` instead of picking
+    /// one of the completions when the puzzle is ambiguous.
+    pub fn solve_unique(&mut self) -> Result<(), SolveError> {
+        MatrixSolver::new().solve_unique(self)
+    }
+
+    /// Finds every completion of this board. An empty result means the board has no solution,
+    /// and more than one result means the puzzle is ambiguous.
+    pub fn solve_all(&self) -> Vec<Board> {
+        MatrixSolver::new().solve_all(self)
+    }
+
+    /// Counts the completions of this board, stopping once `limit` have been found. Use a
+    /// limit of 2 to cheaply distinguish between no, one, and multiple solutions.
+    pub fn count_solutions(&self, limit: usize) -> usize {
+        MatrixSolver::new().count_solutions(self, limit)
+    }
+
+    /// Whether this board has exactly one completion.
+    pub fn has_unique_solution(&self) -> bool {
+        self.count_solutions(2) == 1
+    }
+
+    /// Whether this board is a minimal puzzle: it has a unique solution, and removing any one of
+    /// its givens would allow more than one.
+    pub fn is_minimal(&self) -> bool {
+        MatrixSolver::new().is_minimal(self)
+    }
+
+    /// Finds the simplest logical step that makes progress on the board, explained for the player.
+    /// Only that one step is revealed, never the rest of the solution. Returns None if the board
+    /// is complete, breaks the rules, or needs techniques beyond the `LogicalSolver`. Pencil marks
+    /// are derived from the entries, so use `LogicalSolver::hint` to take a player's own
+    /// eliminations into account.
+    pub fn hint(&self) -> Option<Hint> {
+        if !self.validate().is_empty() {
+            return None;
         }
+        LogicalSolver::new().hint(&Candidates::from_board(self))
+    }
 
-        Ok(())
+    /// Records a full logical solve of the board, step by step. Fails if the givens contradict
+    /// each other. See `Trace`.
+    pub fn trace(&self) -> Result<Trace, SolveError> {
+        LogicalSolver::new().trace(self)
     }
 
-    pub fn init_matrix(&self, matrix: &mut Matrix) {
-        // Reset matrix.
-        matrix.clear();
+    /// Rates how hard the puzzle is for a person to solve. See `Rater`.
+    pub fn rate(&self) -> Result<Rating, SolveError> {
+        Rater::new().rate(self)
+    }
 
-        // First set up the full Sudoku exact cover matrix by adding entries for every combination of
-        // row, column, and number
-        for row in 0..9 {
-            for column in 0..9 {
-                for number in 1..10 {
-                    let possibility = Possibility::new(row, column, number);
-                    let matrix_row = possibility.get_matrix_row();
-                    matrix.set_entry(matrix_row, possibility.get_matrix_column(Constraint::Cell));
-                    matrix.set_entry(matrix_row, possibility.get_matrix_column(Constraint::Row));
-                    matrix.set_entry(matrix_row, possibility.get_matrix_column(Constraint::Column));
-                    matrix.set_entry(matrix_row, possibility.get_matrix_column(Constraint::Box));
+    // Fails if any givens repeat a number within a row, column, or box. Covering the exact cover
+    // matrix columns of such givens would cover the same column twice, which corrupts the dancing
+    // links, so solvers must check this before covering anything.
+    pub(crate) fn check_givens(&self) -> Result<(), SolveError> {
+        let conflicts = self.validate();
+        if conflicts.is_empty() {
+            Ok(())
+        } else {
+            Err(SolveError::ContradictoryGivens(conflicts))
+        }
+    }
+
+    /// Finds every pair of entries that repeat a number within a row, column, or box, without
+    /// running any solver. An empty result means the board breaks no rules, although it may
+    /// still be incomplete or unsolvable. A pair of cells sharing both a row and a box is
+    /// reported once for each constraint.
+    pub fn validate(&self) -> Vec<Conflict> {
+        let mut conflicts = Vec::new();
+        for &constraint in &[Constraint::Row, Constraint::Column, Constraint::Box] {
+            for unit in 0..9 {
+                let cells = unit_cells(constraint, unit);
+                for (i, &first) in cells.iter().enumerate() {
+                    let number = match self.get_entry(first.0, first.1) {
+                        Some(number) => number,
+                        None => continue
+                    };
+                    for &second in &cells[i + 1..] {
+                        if self.get_entry(second.0, second.1) == Some(number) {
+                            conflicts.push(Conflict { first, second, number, constraint });
+                        }
+                    }
                 }
             }
         }
+        conflicts
+    }
+
+    /// Whether every cell has an entry.
+    pub fn is_complete(&self) -> bool {
+        self.entries.iter().all(|&entry| entry != 0)
+    }
 
-        // Next remove options from the full exact cover matrix by covering columns that correspond to
-        // possibilities that are already known.
+    /// Whether every cell has an entry and no entries conflict, ie the board is a valid solution.
+    pub fn is_solved(&self) -> bool {
+        self.is_complete() && self.validate().is_empty()
+    }
+
+    // Convert solution rows to Sudoku possibilities and record in the board.
+    pub(crate) fn apply_solution_rows(&mut self, solution_rows: &[usize]) {
+        for &matrix_row in solution_rows {
+            let possibility = Possibility::from_matrix_row(matrix_row);
+            self.entries[possibility.column + possibility.row * 9] = possibility.number;
+        }
+    }
+
+    // Fails without touching the matrix if the givens contradict each other, see `check_givens`.
+    pub fn init_matrix(&self, matrix: &mut Matrix) -> Result<(), SolveError> {
+        self.check_givens()?;
+        // Reset matrix.
+        matrix.clear();
+        // First set up the full Sudoku exact cover matrix.
+        fill_matrix(matrix);
+        // Next remove options corresponding to knowns.
+        self.cover_givens(matrix);
+        Ok(())
+    }
+
+    // Removes options from the full exact cover matrix by covering columns that correspond to
+    // possibilities that are already known.
+    pub(crate) fn cover_givens(&self, matrix: &mut Matrix) {
         for row in 0..9 {
             for column in 0..9 {
                 let entry = self.entries[column + row * 9];
@@ -147,6 +313,46 @@ impl Board {
         }
     }
 
+    // Undoes `cover_givens`, returning the matrix to the state it was in beforehand. Columns
+    // must be uncovered in exactly the reverse order that they were covered in.
+    pub(crate) fn uncover_givens(&self, matrix: &mut Matrix) {
+        for row in (0..9).rev() {
+            for column in (0..9).rev() {
+                let entry = self.entries[column + row * 9];
+                if entry == 0 {
+                    continue;
+                }
+
+                let possibility = Possibility::new(row, column, entry);
+                matrix.uncover_column(possibility.get_matrix_column(Constraint::Box));
+                matrix.uncover_column(possibility.get_matrix_column(Constraint::Column));
+                matrix.uncover_column(possibility.get_matrix_column(Constraint::Row));
+                matrix.uncover_column(possibility.get_matrix_column(Constraint::Cell));
+            }
+        }
+    }
+
+    /// Creates a board from rows of entries, where 0 means the entry is unknown.
+    pub fn from_array(rows: [[u8; 9]; 9]) -> Board {
+        let mut entries = [0usize; 9 * 9];
+        for (row, row_entries) in rows.iter().enumerate() {
+            for (column, &entry) in row_entries.iter().enumerate() {
+                assert!(entry <= 9, "Entry {} at ({}, {}) must be in [0, 9]", entry, row, column);
+                entries[column + row * 9] = entry as usize;
+            }
+        }
+        Board { entries }
+    }
+
+    /// Returns the rows of entries, where 0 means the entry is unknown. The inverse of `from_array`.
+    pub fn to_array(&self) -> [[u8; 9]; 9] {
+        let mut rows = [[0u8; 9]; 9];
+        for (i, &entry) in self.entries.iter().enumerate() {
+            rows[i / 9][i % 9] = entry as u8;
+        }
+        rows
+    }
+
     pub fn from_singleline_str(input: &str) -> Result<Board, ParseBoardError> {
         let mut entries = [0usize; 9 * 9];
         let mut i = 0;
@@ -155,7 +361,7 @@ impl Board {
                 if i >= entries.len() {
                     return Err(ParseBoardError::TooManyEntries);
                 }
-                assert!(value >= 0 && value <= 9);
+                assert!(value <= 9);
                 entries[i] = value as usize;
                 i += 1;
             }
@@ -174,7 +380,7 @@ impl Board {
                 return Err(ParseBoardError::TooManyEntries);
             }
             if let Some(value) = c.to_digit(10) {
-                assert!(value >= 1 && value <= 9);
+                assert!((1..=9).contains(&value));
                 entries[i] = value as usize;
                 i += 1;
             } else if '_' == c {
@@ -188,6 +394,60 @@ impl Board {
     }
 }
 
+// Returns the index of the box containing a cell. Boxes are numbered in row-major order.
+pub(crate) fn box_of(row: usize, column: usize) -> usize {
+    (column / 3) + (row / 3) * 3
+}
+
+// Returns the index of the row, column, or box containing a cell, as understood by `unit_cells`.
+pub(crate) fn unit_of(constraint: Constraint, row: usize, column: usize) -> usize {
+    match constraint {
+        Constraint::Row => row,
+        Constraint::Column => column,
+        Constraint::Box => box_of(row, column),
+        Constraint::Cell => panic!("Cells are not units")
+    }
+}
+
+// Returns the (row, column) coordinates of the cells in a row, column, or box, in row-major order.
+// Rows and columns are numbered top to bottom and left to right, and boxes in row-major order.
+pub(crate) fn unit_cells(constraint: Constraint, index: usize) -> [(usize, usize); 9] {
+    assert!(index < 9);
+    let mut cells = [(0, 0); 9];
+    for (i, cell) in cells.iter_mut().enumerate() {
+        *cell = match constraint {
+            Constraint::Row => (index, i),
+            Constraint::Column => (i, index),
+            Constraint::Box => ((index / 3) * 3 + i / 3, (index % 3) * 3 + i % 3),
+            Constraint::Cell => panic!("Cells are not units")
+        };
+    }
+    cells
+}
+
+// Sets up the full Sudoku exact cover matrix by adding entries for every combination of
+// row, column, and number. The matrix must be freshly cleared.
+pub(crate) fn fill_matrix(matrix: &mut Matrix) {
+    for row in 0..9 {
+        for column in 0..9 {
+            for number in 1..10 {
+                let possibility = Possibility::new(row, column, number);
+                let matrix_row = possibility.get_matrix_row();
+                matrix.set_entry(matrix_row, possibility.get_matrix_column(Constraint::Cell));
+                matrix.set_entry(matrix_row, possibility.get_matrix_column(Constraint::Row));
+                matrix.set_entry(matrix_row, possibility.get_matrix_column(Constraint::Column));
+                matrix.set_entry(matrix_row, possibility.get_matrix_column(Constraint::Box));
+            }
+        }
+    }
+}
+
+impl Default for Board {
+    fn default() -> Board {
+        Board::new()
+    }
+}
+
 impl Debug for Board {
     fn fmt(&self, f: &mut Formatter) -> Result<(), ::std::fmt::Error> {
         Display::fmt(self, f)
@@ -199,7 +459,7 @@ impl Display for Board {
         // Write one line at a time so it can be padded/indented appropriately
         let mut buf = String::new();
         for row in 0..9 {
-            fmt_row(self, f, &mut buf, row);
+            fmt_row(self, f, &mut buf, row)?;
         }
         Ok(())
     }
@@ -208,7 +468,6 @@ impl Display for Board {
 fn fmt_row(board: &Board, f: &mut Formatter, buf: &mut String, row: usize) -> Result<(), ::std::fmt::Error> {
     buf.clear();
     for col in 0..8 {
-        let i = col + row * 9;
         write!(buf, "{} ", board.entries[col + row * 9])?;
     }
     let last_row = row == 8;
@@ -217,7 +476,7 @@ fn fmt_row(board: &Board, f: &mut Formatter, buf: &mut String, row: usize) -> Re
     } else {
         writeln!(buf, "{}", board.entries[8 + row * 9])?;
     }
-    f.pad(&buf)?;
+    f.pad(buf)?;
     Ok(())
 }
 
@@ -228,6 +487,26 @@ pub enum ParseBoardError {
     TooFewEntries
 }
 
+impl Display for Constraint {
+    fn fmt(&self, f: &mut Formatter) -> Result<(), ::std::fmt::Error> {
+        f.write_str(match *self {
+            Constraint::Cell => "cell",
+            Constraint::Row => "row",
+            Constraint::Column => "column",
+            Constraint::Box => "box"
+        })
+    }
+}
+
+impl Display for Conflict {
+    fn fmt(&self, f: &mut Formatter) -> Result<(), ::std::fmt::Error> {
+        write!(f, "r{}c{} and r{}c{} both contain {} in the same {}",
+            self.first.0 + 1, self.first.1 + 1,
+            self.second.0 + 1, self.second.1 + 1,
+            self.number, self.constraint)
+    }
+}
+
 impl Eq for Board {}
 
 impl PartialEq for Board {
@@ -243,7 +522,9 @@ impl PartialEq for Board {
 
 #[cfg(test)]
 mod tests {
-    use super::{Possibility, Constraint, Board};
+    use SolveError;
+    use matrix::Matrix;
+    use super::{unit_cells, Possibility, Constraint, Conflict, Board};
     
     #[test]
     fn possibility_from_matrix_row_consistent_with_get_matrix_row() {
@@ -341,6 +622,59 @@ mod tests {
         }
     }
 
+    #[test]
+    fn board_set_and_clear_entry() {
+        let mut board = Board::new();
+        board.set_entry(4, 7, 3);
+        assert_eq!(Some(3), board.get_entry(4, 7));
+        board.set_entry(4, 7, 9);
+        assert_eq!(Some(9), board.get_entry(4, 7));
+        board.clear_entry(4, 7);
+        assert_eq!(None, board.get_entry(4, 7));
+        assert_eq!(Board::new(), board);
+    }
+
+    #[test]
+    fn board_from_array() {
+        let rows = [
+            [5, 3, 0, 0, 7, 0, 0, 0, 0],
+            [6, 0, 0, 1, 9, 5, 0, 0, 0],
+            [0, 9, 8, 0, 0, 0, 0, 6, 0],
+            [8, 0, 0, 0, 6, 0, 0, 0, 3],
+            [4, 0, 0, 8, 0, 3, 0, 0, 1],
+            [7, 0, 0, 0, 2, 0, 0, 0, 6],
+            [0, 6, 0, 0, 0, 0, 2, 8, 0],
+            [0, 0, 0, 4, 1, 9, 0, 0, 5],
+            [0, 0, 0, 0, 8, 0, 0, 7, 9]
+        ];
+        let board = Board::from_array(rows);
+        assert_eq!(Board::from_singleline_str("530070000600195000098000060800060003400803001700020006060000280000419005000080079").unwrap(), board);
+        assert_eq!(rows, board.to_array());
+    }
+
+    #[test]
+    fn board_cell_iterators() {
+        let board = Board::from_singleline_str("530070000600195000098000060800060003400803001700020006060000280000419005000080079").unwrap();
+
+        let cells: Vec<_> = board.cells().collect();
+        assert_eq!(81, cells.len());
+        assert_eq!((0, 0, Some(5)), cells[0]);
+        assert_eq!((0, 2, None), cells[2]);
+        assert_eq!((8, 8, Some(9)), cells[80]);
+
+        let row: Vec<_> = board.row_cells(1).map(|(_, _, entry)| entry).collect();
+        assert_eq!(vec![Some(6), None, None, Some(1), Some(9), Some(5), None, None, None], row);
+
+        let column: Vec<_> = board.column_cells(4).map(|(_, _, entry)| entry).collect();
+        assert_eq!(vec![Some(7), Some(9), None, Some(6), None, Some(2), None, Some(1), Some(8)], column);
+
+        let box_: Vec<_> = board.box_cells(8).collect();
+        assert_eq!((6, 6, Some(2)), box_[0]);
+        assert_eq!((8, 8, Some(9)), box_[8]);
+        assert_eq!(vec![Some(2), Some(8), None, None, None, Some(5), None, Some(7), Some(9)],
+            box_.iter().map(|&(_, _, entry)| entry).collect::<Vec<_>>());
+    }
+
     #[test]
     fn board_from_multiline_str() {
         let board = Board::from_multiline_str(
@@ -391,6 +725,149 @@ mod tests {
         assert_eq!(Some(2), board.get_entry(5, 4));
     }
 
+    #[test]
+    fn board_solve_all_unique() {
+        let board = Board::from_singleline_str("530070000600195000098000060800060003400803001700020006060000280000419005000080079").unwrap();
+        let solutions = board.solve_all();
+        assert_eq!(1, solutions.len());
+        assert_eq!(Board::from_singleline_str("534678912672195348198342567859761423426853791713924856961537284287419635345286179").unwrap(), solutions[0]);
+    }
+
+    #[test]
+    fn board_solve_all_ambiguous() {
+        // A solved board with a deadly pattern of 4 and 8 removed from rows 1 and 6.
+        let board = Board::from_singleline_str("534678912672195300198342567859761423426853791713924856961537200287419635345286179").unwrap();
+        let solutions = board.solve_all();
+        assert_eq!(2, solutions.len());
+        let mut corners: Vec<_> = solutions.iter().map(|solution| solution.get_entry(1, 7)).collect();
+        corners.sort();
+        assert_eq!(vec![Some(4), Some(8)], corners);
+    }
+
+    #[test]
+    fn board_count_solutions() {
+        let unique = Board::from_singleline_str("530070000600195000098000060800060003400803001700020006060000280000419005000080079").unwrap();
+        assert_eq!(1, unique.count_solutions(2));
+        assert!(unique.has_unique_solution());
+
+        let ambiguous = Board::from_singleline_str("534678912672195300198342567859761423426853791713924856961537200287419635345286179").unwrap();
+        assert_eq!(1, ambiguous.count_solutions(1));
+        assert_eq!(2, ambiguous.count_solutions(2));
+        assert_eq!(2, ambiguous.count_solutions(100));
+        assert!(!ambiguous.has_unique_solution());
+
+        // The empty board has billions of solutions, so this only terminates because of the limit.
+        assert_eq!(10, Board::new().count_solutions(10));
+    }
+
+    #[test]
+    fn board_is_minimal() {
+        // Every given of this puzzle is needed.
+        let minimal = Board::from_singleline_str("000000090032006050000000004400003581000074000600908000008700003100000040070009068").unwrap();
+        assert!(minimal.is_minimal());
+
+        // Adding a given from the solution keeps it unique, but makes that given redundant.
+        let mut redundant = minimal.clone();
+        redundant.set_entry(0, 0, minimal.solve_all()[0].get_entry(0, 0).unwrap());
+        assert!(redundant.has_unique_solution());
+        assert!(!redundant.is_minimal());
+
+        let ambiguous = Board::from_singleline_str("534678912672195300198342567859761423426853791713924856961537200287419635345286179").unwrap();
+        assert!(!ambiguous.is_minimal());
+    }
+
+    #[test]
+    fn board_solve_errors() {
+        let mut ambiguous = Board::from_singleline_str("534678912672195300198342567859761423426853791713924856961537200287419635345286179").unwrap();
+        assert_eq!(Err(SolveError::MultipleSolutions), ambiguous.clone().solve_unique());
+        assert_eq!(Ok(()), ambiguous.solve());
+
+        let mut contradictory = Board::from_singleline_str("500000005000000000000000000000000000000000000000000000000000000000000000000000000").unwrap();
+        let conflicts = vec![Conflict { first: (0, 0), second: (0, 8), number: 5, constraint: Constraint::Row }];
+        assert_eq!(Err(SolveError::ContradictoryGivens(conflicts.clone())), contradictory.clone().solve_unique());
+        assert_eq!(Err(SolveError::ContradictoryGivens(conflicts)), contradictory.solve());
+
+        let mut unsolvable = Board::from_singleline_str("123456780000000000000000000000000000000000009000000000000000000000000000000000000").unwrap();
+        assert_eq!(Err(SolveError::NoSolution), unsolvable.clone().solve_unique());
+        assert_eq!(Err(SolveError::NoSolution), unsolvable.solve());
+    }
+
+    #[test]
+    fn init_matrix_rejects_contradictory_givens() {
+        let mut matrix = Matrix::new(9 * 9 * 9, 9 * 9 * 4, 9 * 9 * 9 * 4);
+        let pristine = matrix.clone();
+
+        let contradictory = Board::from_singleline_str("500000005000000000000000000000000000000000000000000000000000000000000000000000000").unwrap();
+        let conflicts = vec![Conflict { first: (0, 0), second: (0, 8), number: 5, constraint: Constraint::Row }];
+        assert_eq!(Err(SolveError::ContradictoryGivens(conflicts)), contradictory.init_matrix(&mut matrix));
+        assert!(pristine == matrix);
+
+        let board = Board::from_singleline_str("530070000600195000098000060800060003400803001700020006060000280000419005000080079").unwrap();
+        assert_eq!(Ok(()), board.init_matrix(&mut matrix));
+        assert_eq!(1, matrix.count_solutions(2));
+    }
+
+    #[test]
+    fn board_validate() {
+        let board = Board::from_multiline_str(
+        //   0 1 2 3 4 5 6 7 8
+            "5 3 _ _ 7 _ _ _ 3\
+             6 5 _ 1 9 5 _ _ _\
+             _ 9 8 _ _ _ _ 6 _\
+             8 _ _ _ 6 _ _ _ 3\
+             4 _ _ 8 _ 3 _ _ 1\
+             7 _ _ _ 2 _ _ _ 6\
+             _ 6 _ _ _ _ 2 8 _\
+             _ _ _ 4 1 9 _ _ 5\
+             _ _ _ _ 8 _ _ 7 9").unwrap();
+        assert_eq!(vec![
+            Conflict { first: (0, 1), second: (0, 8), number: 3, constraint: Constraint::Row },
+            Conflict { first: (1, 1), second: (1, 5), number: 5, constraint: Constraint::Row },
+            Conflict { first: (0, 8), second: (3, 8), number: 3, constraint: Constraint::Column },
+            Conflict { first: (0, 0), second: (1, 1), number: 5, constraint: Constraint::Box }
+        ], board.validate());
+        assert_eq!("r1c2 and r1c9 both contain 3 in the same row", board.validate()[0].to_string());
+
+        assert!(Board::from_singleline_str("530070000600195000098000060800060003400803001700020006060000280000419005000080079").unwrap().validate().is_empty());
+    }
+
+    #[test]
+    fn board_is_complete_and_is_solved() {
+        let unsolved = Board::from_singleline_str("530070000600195000098000060800060003400803001700020006060000280000419005000080079").unwrap();
+        assert!(!unsolved.is_complete());
+        assert!(!unsolved.is_solved());
+
+        let mut solved = unsolved.clone();
+        solved.solve().unwrap();
+        assert!(solved.is_complete());
+        assert!(solved.is_solved());
+
+        // Swapping two numbers within a row keeps the board complete but breaks the columns.
+        let swapped = Board::from_singleline_str("354678912672195348198342567859761423426853791713924856961537284287419635345286179").unwrap();
+        assert!(swapped.is_complete());
+        assert!(!swapped.is_solved());
+    }
+
+    #[test]
+    fn unit_cells_cover_board() {
+        for &constraint in &[Constraint::Row, Constraint::Column, Constraint::Box] {
+            let mut seen = [false; 9 * 9];
+            for unit in 0..9 {
+                for &(row, column) in unit_cells(constraint, unit).iter() {
+                    let possibility = Possibility::new(row, column, 1);
+                    let expected_column = Possibility::new(unit / 3 * 3, unit % 3 * 3, 1);
+                    match constraint {
+                        Constraint::Row => assert_eq!(unit, row),
+                        Constraint::Column => assert_eq!(unit, column),
+                        _ => assert_eq!(expected_column.get_matrix_column(Constraint::Box), possibility.get_matrix_column(Constraint::Box))
+                    }
+                    assert!(!seen[column + row * 9]);
+                    seen[column + row * 9] = true;
+                }
+            }
+        }
+    }
+
     #[test]
     fn singleline_and_multiline_board_equivalency() {
         let a = Board::from_multiline_str(
@@ -411,4 +888,18 @@ mod tests {
             }
         }
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn hint_reveals_one_step() {
+        let board = Board::from_singleline_str("530070000600195000098000060800060003400803001700020006060000280000419005000080079").unwrap();
+        let hint = board.hint().unwrap();
+        assert_eq!(1, hint.step.placements.len() + hint.step.eliminations.len());
+        assert!(!hint.step.cells.is_empty());
+        assert!(!hint.explanation.is_empty());
+
+        let mut solution = board.clone();
+        solution.solve().unwrap();
+        assert!(solution.hint().is_none());
+        assert!(Board::from_singleline_str("110000000000000000000000000000000000000000000000000000000000000000000000000000000").unwrap().hint().is_none());
+    }
+}
@@ -0,0 +1,311 @@
+use std::fmt::{Display, Debug, Formatter};
+
+use board::{unit_cells, unit_of, Board, Constraint, Possibility};
+
+/// A set of numbers in [1, 9], typically the numbers that could still go in a cell.
+#[derive(Clone, Copy, Default, Eq, PartialEq, Hash)]
+pub struct CandidateSet(u16);
+
+/// Pencil marks for a board. Every cell either has an entry, or a set of candidate numbers that
+/// could still be placed there. Candidates start out as every number not already used by the
+/// cell's row, column, or box, and are narrowed further by eliminating them one at a time.
+#[derive(Clone, Eq, PartialEq)]
+pub struct Candidates {
+    // Entries can be in the range [0, 9], 0 meaning unknown, in the same row-major order as `Board`.
+    entries: [usize; 9 * 9],
+    // The remaining candidates for each cell, in row-major order. Always empty for cells with an entry.
+    cells: [CandidateSet; 9 * 9]
+}
+
+impl CandidateSet {
+    // Bits 1 through 9 set.
+    pub(crate) const ALL_BITS: u16 = 0b11_1111_1110;
+
+    pub fn empty() -> CandidateSet {
+        CandidateSet(0)
+    }
+
+    pub fn all() -> CandidateSet {
+        CandidateSet(CandidateSet::ALL_BITS)
+    }
+
+    pub fn contains(self, number: usize) -> bool {
+        (1..=9).contains(&number) && self.0 & (1 << number) != 0
+    }
+
+    pub fn insert(&mut self, number: usize) {
+        assert!((1..=9).contains(&number));
+        self.0 |= 1 << number;
+    }
+
+    /// Removes a number, returning whether it was in the set.
+    pub fn remove(&mut self, number: usize) -> bool {
+        let was_present = self.contains(number);
+        self.0 &= !(1 << number);
+        was_present
+    }
+
+    pub fn len(self) -> usize {
+        self.0.count_ones() as usize
+    }
+
+    pub fn is_empty(self) -> bool {
+        self.0 == 0
+    }
+
+    /// The only number in the set, if it has exactly one.
+    pub fn single(self) -> Option<usize> {
+        if self.len() == 1 { Some(self.0.trailing_zeros() as usize) } else { None }
+    }
+
+    pub fn union(self, other: CandidateSet) -> CandidateSet {
+        CandidateSet(self.0 | other.0)
+    }
+
+    pub fn intersection(self, other: CandidateSet) -> CandidateSet {
+        CandidateSet(self.0 & other.0)
+    }
+
+    pub fn difference(self, other: CandidateSet) -> CandidateSet {
+        CandidateSet(self.0 & !other.0)
+    }
+
+    /// Iterates over the numbers in the set in increasing order.
+    pub fn iter(self) -> impl Iterator<Item = usize> {
+        (1..10).filter(move |&number| self.contains(number))
+    }
+}
+
+impl Debug for CandidateSet {
+    fn fmt(&self, f: &mut Formatter) -> Result<(), ::std::fmt::Error> {
+        Display::fmt(self, f)
+    }
+}
+
+impl Display for CandidateSet {
+    fn fmt(&self, f: &mut Formatter) -> Result<(), ::std::fmt::Error> {
+        for number in self.iter() {
+            write!(f, "{}", number)?;
+        }
+        Ok(())
+    }
+}
+
+impl Candidates {
+    /// Derives the candidates of every empty cell from the entries on a board.
+    pub fn from_board(board: &Board) -> Candidates {
+        let mut entries = [0usize; 9 * 9];
+        for (row, column, entry) in board.cells() {
+            entries[column + row * 9] = entry.unwrap_or(0);
+        }
+        let mut candidates = Candidates {
+            entries,
+            cells: [CandidateSet::empty(); 9 * 9]
+        };
+        candidates.rederive();
+        candidates
+    }
+
+    /// Recomputes every empty cell's candidates from the current entries, discarding any
+    /// eliminations that have been made.
+    pub fn rederive(&mut self) {
+        for i in 0..9 * 9 {
+            self.cells[i] = if self.entries[i] == 0 { CandidateSet::all() } else { CandidateSet::empty() };
+        }
+        for i in 0..9 * 9 {
+            if self.entries[i] != 0 {
+                self.eliminate_from_peers(i / 9, i % 9, self.entries[i]);
+            }
+        }
+    }
+
+    /// Converts back to a board holding just the entries.
+    pub fn to_board(&self) -> Board {
+        let mut board = Board::new();
+        for (i, &entry) in self.entries.iter().enumerate() {
+            if entry != 0 {
+                board.set_entry(i / 9, i % 9, entry);
+            }
+        }
+        board
+    }
+
+    pub fn get_entry(&self, row: usize, column: usize) -> Option<usize> {
+        assert!(row < 9);
+        assert!(column < 9);
+        let entry = self.entries[column + row * 9];
+        if entry == 0 { None } else { Some(entry) }
+    }
+
+    /// The remaining candidates of a cell. Empty if the cell has an entry.
+    pub fn get_candidates(&self, row: usize, column: usize) -> CandidateSet {
+        assert!(row < 9);
+        assert!(column < 9);
+        self.cells[column + row * 9]
+    }
+
+    pub fn is_candidate(&self, row: usize, column: usize, number: usize) -> bool {
+        self.get_candidates(row, column).contains(number)
+    }
+
+    /// Removes a candidate from a cell, returning whether it was a candidate beforehand.
+    pub fn eliminate(&mut self, row: usize, column: usize, number: usize) -> bool {
+        assert!(row < 9);
+        assert!(column < 9);
+        self.cells[column + row * 9].remove(number)
+    }
+
+    /// Places a number in an empty cell, and eliminates it as a candidate from every other
+    /// cell in the same row, column, and box.
+    pub fn place(&mut self, row: usize, column: usize, number: usize) {
+        assert!(self.get_entry(row, column).is_none(), "Cell ({}, {}) already has an entry", row, column);
+        assert!((1..=9).contains(&number));
+        self.entries[column + row * 9] = number;
+        self.cells[column + row * 9] = CandidateSet::empty();
+        self.eliminate_from_peers(row, column, number);
+    }
+
+    /// Iterates over every remaining candidate as a `Possibility`, in row-major order.
+    pub fn possibilities<'a>(&'a self) -> impl Iterator<Item = Possibility> + 'a {
+        (0..9 * 9).flat_map(move |i| {
+            self.cells[i].iter().map(move |number| Possibility::new(i / 9, i % 9, number))
+        })
+    }
+
+    /// Whether every cell has an entry.
+    pub fn is_complete(&self) -> bool {
+        self.entries.iter().all(|&entry| entry != 0)
+    }
+
+    /// Whether some empty cell has run out of candidates, meaning the board can't be solved.
+    pub fn has_empty_cell(&self) -> bool {
+        (0..9 * 9).any(|i| self.entries[i] == 0 && self.cells[i].is_empty())
+    }
+
+    fn eliminate_from_peers(&mut self, row: usize, column: usize, number: usize) {
+        for &constraint in &[Constraint::Row, Constraint::Column, Constraint::Box] {
+            for &(peer_row, peer_column) in unit_cells(constraint, unit_of(constraint, row, column)).iter() {
+                self.cells[peer_column + peer_row * 9].remove(number);
+            }
+        }
+    }
+}
+
+impl Debug for Candidates {
+    fn fmt(&self, f: &mut Formatter) -> Result<(), ::std::fmt::Error> {
+        Display::fmt(self, f)
+    }
+}
+
+impl Display for Candidates {
+    // Writes one line per row. Entries are written as-is, and empty cells as their candidates
+    // in braces, eg {149}.
+    fn fmt(&self, f: &mut Formatter) -> Result<(), ::std::fmt::Error> {
+        for row in 0..9 {
+            for column in 0..9 {
+                if column > 0 {
+                    write!(f, " ")?;
+                }
+                match self.get_entry(row, column) {
+                    Some(entry) => write!(f, "{}", entry)?,
+                    None => write!(f, "{{{}}}", self.get_candidates(row, column))?
+                }
+            }
+            if row < 8 {
+                writeln!(f)?;
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use board::{Board, Possibility};
+    use super::{CandidateSet, Candidates};
+
+    fn puzzle() -> Board {
+        Board::from_singleline_str("530070000600195000098000060800060003400803001700020006060000280000419005000080079").unwrap()
+    }
+
+    fn set(numbers: &[usize]) -> CandidateSet {
+        let mut set = CandidateSet::empty();
+        for &number in numbers {
+            set.insert(number);
+        }
+        set
+    }
+
+    #[test]
+    fn candidate_set_operations() {
+        let mut a = set(&[1, 4, 9]);
+        assert_eq!(3, a.len());
+        assert!(a.contains(4));
+        assert!(!a.contains(5));
+        assert_eq!(vec![1, 4, 9], a.iter().collect::<Vec<_>>());
+        assert!(a.remove(4));
+        assert!(!a.remove(4));
+        assert_eq!(set(&[1, 9]), a);
+        assert_eq!(None, a.single());
+        assert_eq!(Some(9), set(&[9]).single());
+        assert_eq!(set(&[1, 2, 9]), a.union(set(&[2])));
+        assert_eq!(set(&[9]), a.intersection(set(&[2, 9])));
+        assert_eq!(set(&[1]), a.difference(set(&[2, 9])));
+        assert_eq!(9, CandidateSet::all().len());
+        assert!(CandidateSet::empty().is_empty());
+        assert_eq!("19", a.to_string());
+    }
+
+    #[test]
+    fn from_board_derives_candidates() {
+        let candidates = Candidates::from_board(&puzzle());
+        assert_eq!(Some(5), candidates.get_entry(0, 0));
+        assert!(candidates.get_candidates(0, 0).is_empty());
+        assert_eq!(set(&[1, 2, 4]), candidates.get_candidates(0, 2));
+        assert_eq!(set(&[5]), candidates.get_candidates(4, 4));
+        assert_eq!(set(&[2, 3]), candidates.get_candidates(7, 0));
+        assert!(!candidates.has_empty_cell());
+        assert!(!candidates.is_complete());
+        assert_eq!(puzzle(), candidates.to_board());
+    }
+
+    #[test]
+    fn eliminate_and_rederive() {
+        let mut candidates = Candidates::from_board(&puzzle());
+        assert!(candidates.eliminate(0, 2, 4));
+        assert!(!candidates.eliminate(0, 2, 4));
+        assert!(!candidates.eliminate(0, 0, 5));
+        assert_eq!(set(&[1, 2]), candidates.get_candidates(0, 2));
+
+        candidates.rederive();
+        assert_eq!(set(&[1, 2, 4]), candidates.get_candidates(0, 2));
+    }
+
+    #[test]
+    fn place_eliminates_from_peers() {
+        let mut candidates = Candidates::from_board(&puzzle());
+        candidates.place(4, 4, 5);
+        assert_eq!(Some(5), candidates.get_entry(4, 4));
+        assert!(candidates.get_candidates(4, 4).is_empty());
+        // Same row, column, and box respectively.
+        assert!(!candidates.is_candidate(4, 1, 5));
+        assert!(!candidates.is_candidate(2, 4, 5));
+        assert!(!candidates.is_candidate(3, 5, 5));
+
+        let mut expected = puzzle();
+        expected.set_entry(4, 4, 5);
+        assert_eq!(expected, candidates.to_board());
+    }
+
+    #[test]
+    fn possibilities_match_candidates() {
+        let candidates = Candidates::from_board(&puzzle());
+        let possibilities: Vec<Possibility> = candidates.possibilities().collect();
+        let total: usize = (0..81).map(|i| candidates.get_candidates(i / 9, i % 9).len()).sum();
+        assert_eq!(total, possibilities.len());
+        assert_eq!(Possibility::new(0, 2, 1), possibilities[0]);
+        for possibility in possibilities {
+            assert!(candidates.is_candidate(possibility.row(), possibility.column(), possibility.number()));
+        }
+    }
+}
@@ -0,0 +1,203 @@
+use std::collections::HashSet;
+
+use board::Board;
+
+// A board's entries, 0 meaning unknown, indexed by row and then column.
+type Grid = [[u8; 9]; 9];
+
+// Every order of three things.
+const ORDERS: [[usize; 3]; 6] = [[0, 1, 2], [0, 2, 1], [1, 0, 2], [1, 2, 0], [2, 0, 1], [2, 1, 0]];
+
+impl Board {
+    /// Finds the minlex form of the board: the lexicographically smallest of the 81 digit strings,
+    /// 0 meaning unknown, of all the boards it can be turned into by transposing, reordering bands,
+    /// stacks, and the rows and columns within them, and relabeling the numbers. Boards have the
+    /// same minlex form exactly when they are equivalent puzzles.
+    pub fn minlex(&self) -> String {
+        let grid = self.to_array();
+        let mut search = Search { best: [[u8::MAX; 9]; 9] };
+        // Boards with few givens come out the same under many column orders, which need only be
+        // searched once.
+        let mut searched: HashSet<Grid> = HashSet::new();
+        for &transposed in &[false, true] {
+            for columns in column_orders() {
+                let mut arranged = [[0u8; 9]; 9];
+                for row in 0..9 {
+                    for (column, &from) in columns.iter().enumerate() {
+                        arranged[row][column] = if transposed { grid[from][row] } else { grid[row][from] };
+                    }
+                }
+                if searched.insert(arranged) {
+                    search.search(&arranged, &mut [[0u8; 9]; 9], 0, 0, [false; 9], [0u8; 10]);
+                }
+            }
+        }
+        search.best.iter().flat_map(|row| row.iter()).map(|&entry| (b'0' + entry) as char).collect()
+    }
+
+    /// Whether the board can be turned into the other by transposing, reordering bands, stacks,
+    /// and the rows and columns within them, and relabeling the numbers.
+    pub fn is_equivalent(&self, other: &Board) -> bool {
+        self.minlex() == other.minlex()
+    }
+}
+
+// Every order of the columns which keeps the stacks together.
+fn column_orders() -> Vec<[usize; 9]> {
+    let mut orders = Vec::with_capacity(1296);
+    for stacks in &ORDERS {
+        for first in &ORDERS {
+            for second in &ORDERS {
+                for third in &ORDERS {
+                    let mut order = [0; 9];
+                    for (i, within) in [first, second, third].iter().enumerate() {
+                        for j in 0..3 {
+                            order[i * 3 + j] = stacks[i] * 3 + within[j];
+                        }
+                    }
+                    orders.push(order);
+                }
+            }
+        }
+    }
+    orders
+}
+
+// Searches the orders of the rows, with the columns already arranged, for the smallest string.
+struct Search {
+    // The smallest arrangement found so far.
+    best: Grid
+}
+
+impl Search {
+    // Chooses the row to put at `position`, having already chosen those above it, the last of them
+    // from `band`. Only the rows which come out smallest can lead to the best arrangement, so those
+    // are the only ones tried. `labels` maps numbers to their new labels, with 0 for numbers which
+    // haven't appeared yet.
+    fn search(&mut self, arranged: &Grid, output: &mut Grid, position: usize, band: usize, used: [bool; 9], labels: [u8; 10]) {
+        if position == 9 {
+            if *output < self.best {
+                self.best = *output;
+            }
+            return;
+        }
+
+        // A new band can start from any unused band, otherwise the current band continues.
+        let rows: Vec<usize> = (0..9)
+            .filter(|&row| !used[row] && (position.is_multiple_of(3) || row / 3 == band))
+            .collect();
+
+        let candidates: Vec<(usize, [u8; 9], [u8; 10])> = rows.into_iter()
+            .map(|row| {
+                let (relabeled, labels) = relabel(&arranged[row], labels);
+                (row, relabeled, labels)
+            })
+            .collect();
+        let smallest = match candidates.iter().map(|&(_, relabeled, _)| relabeled).min() {
+            Some(smallest) => smallest,
+            None => return
+        };
+        // Give up if this arrangement is already worse than the best one.
+        if (&output[..position], smallest) > (&self.best[..position], self.best[position]) {
+            return;
+        }
+
+        // Swapping two rows of the same band with the same entries changes nothing, so only the
+        // first of them is tried. This is what keeps boards with few givens, whose rows nearly all
+        // tie, from trying every order of their empty rows.
+        let mut tried: Vec<(usize, [u8; 9])> = Vec::new();
+        for (row, relabeled, labels) in candidates {
+            if relabeled == smallest && !tried.contains(&(row / 3, arranged[row])) {
+                tried.push((row / 3, arranged[row]));
+                let mut used = used;
+                used[row] = true;
+                output[position] = relabeled;
+                self.search(arranged, output, position + 1, row / 3, used, labels);
+            }
+        }
+    }
+}
+
+// Relabels a row, giving numbers which haven't appeared yet the next labels in the order they
+// appear. Returns the relabeled row and the extended labels.
+fn relabel(row: &[u8; 9], mut labels: [u8; 10]) -> ([u8; 9], [u8; 10]) {
+    let mut next = labels.iter().cloned().max().unwrap_or(0) + 1;
+    let mut relabeled = [0; 9];
+    for (column, &entry) in row.iter().enumerate() {
+        if entry != 0 {
+            if labels[entry as usize] == 0 {
+                labels[entry as usize] = next;
+                next += 1;
+            }
+            relabeled[column] = labels[entry as usize];
+        }
+    }
+    (relabeled, labels)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::{Duration, Instant};
+
+    use board::Board;
+
+    const EASY: &str = "530070000600195000098000060800060003400803001700020006060000280000419005000080079";
+
+    // Transposes the board, swaps its first two bands and the last two columns of its middle
+    // stack, and relabels each number n as 10 - n.
+    fn disguise(board: &Board) -> Board {
+        let mut disguised = Board::new();
+        for (row, column, entry) in board.cells() {
+            if let Some(number) = entry {
+                let (row, column) = (column, row);
+                let row = if row < 3 { row + 3 } else if row < 6 { row - 3 } else { row };
+                let column = match column { 4 => 5, 5 => 4, column => column };
+                disguised.set_entry(row, column, 10 - number);
+            }
+        }
+        disguised
+    }
+
+    #[test]
+    fn equivalent_boards_share_minlex() {
+        let board = Board::from_singleline_str(EASY).unwrap();
+        let disguised = disguise(&board);
+        assert_ne!(board, disguised);
+        assert_eq!(board.minlex(), disguised.minlex());
+        assert!(board.is_equivalent(&disguised));
+
+        let other = Board::from_singleline_str("000400002150300080000002009900040000700001800000050090000000200007680000609070010").unwrap();
+        assert!(!board.is_equivalent(&other));
+    }
+
+    #[test]
+    fn minlex_is_smallest() {
+        let board = Board::from_singleline_str(EASY).unwrap();
+        let minlex = board.minlex();
+        assert_eq!(81, minlex.len());
+        assert!(minlex.as_str() <= EASY);
+        assert_eq!(30, minlex.chars().filter(|&c| c != '0').count());
+        // The minlex form is its own minlex form.
+        assert_eq!(minlex, Board::from_singleline_str(&minlex).unwrap().minlex());
+    }
+
+    #[test]
+    fn minlex_of_sparse_boards() {
+        // Nearly every row ties on boards like these, which must not make the search try every
+        // order of the rows. Allows plenty of slack for unoptimized builds.
+        let start = Instant::now();
+        assert_eq!("0".repeat(81), Board::new().minlex());
+        let mut one = Board::new();
+        one.set_entry(4, 4, 7);
+        assert_eq!(format!("{}1", "0".repeat(80)), one.minlex());
+        assert!(start.elapsed() < Duration::from_millis(500), "took {:?}", start.elapsed());
+    }
+
+    #[test]
+    fn minlex_of_solution() {
+        let solution = Board::from_singleline_str("534678912672195348198342567859761423426853791713924856961537284287419635345286179").unwrap();
+        let minlex = solution.minlex();
+        assert!(minlex.starts_with("123456789"));
+        assert_eq!(minlex, disguise(&solution).minlex());
+    }
+}
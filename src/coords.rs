@@ -15,7 +15,11 @@ impl DenseRow {
         DenseRow(dense_row_index)
     }
 
-    pub fn to_sparse(&self) -> SparseRow {
+    pub fn index(self) -> usize {
+        self.0
+    }
+
+    pub fn to_sparse(self) -> SparseRow {
         SparseRow(self.0)
     }
 
@@ -33,7 +37,7 @@ impl DenseRow {
             },
             Constraint::Row => {
                 let row_group = self.0 / 9;
-                if row_group % 9 == 0 {
+                if row_group.is_multiple_of(9) {
                     DenseRow(self.0 + 9 * 8)
                 } else {
                     DenseRow(self.0 - 9)
@@ -41,7 +45,7 @@ impl DenseRow {
             },
             Constraint::Column => {
                 let row_group = self.0 / (9 * 9);
-                if row_group % 9 == 0 {
+                if row_group.is_multiple_of(9) {
                     DenseRow(self.0 + 9 * 9 * 8)
                 } else {
                     DenseRow(self.0 - 9 * 9)
@@ -49,7 +53,7 @@ impl DenseRow {
             },
             Constraint::Box => {
                 let row_group = self.0 / 9;
-                if row_group % 3 == 0 {
+                if row_group.is_multiple_of(3) {
                     let row_factor = (row_group % (9 * 3)) / 9;
                     if row_factor == 0 {
                         DenseRow(self.0 + 18 * 9 + 9 * 2)
@@ -116,7 +120,13 @@ impl SparseRow {
         SparseRow(sparse_row_index)
     }
 
-    pub fn to_dense(&self, column: &SparseColumn) -> DenseRow {
+    pub fn index(self) -> usize {
+        self.0
+    }
+
+    // Sparse and dense rows currently share the same index space, so the column is unused, but
+    // it is kept so that callers don't depend on that detail of the dense layout.
+    pub fn to_dense(self, _column: &SparseColumn) -> DenseRow {
         DenseRow(self.0)
     }
 }
@@ -127,17 +137,21 @@ impl DenseColumn {
         DenseColumn(dense_column_index)
     }
 
-    pub fn to_sparse(&self, row: &DenseRow) -> SparseColumn {
+    pub fn index(self) -> usize {
+        self.0
+    }
+
+    pub fn to_sparse(self, row: &DenseRow) -> SparseColumn {
         // These formulas were all discovered by looking at the table at http://www.stolaf.edu/people/hansonr/sudoku/exactcovermatrix.htm
         match self.constraint() {
             Constraint::Cell => SparseColumn(row.0 / 9),
-            Constraint::Row => SparseColumn(81 * 1 + row.0 % 9 + 9 * (row.0 / 81)),
+            Constraint::Row => SparseColumn(81 + row.0 % 9 + 9 * (row.0 / 81)),
             Constraint::Column => SparseColumn(81 * 2 + row.0 % 81),
             Constraint::Box => SparseColumn(81 * 3 + row.0 % 9 + 9 * ((row.0 / (9 * 3)) % 3) + 3 * 9 * (row.0 / (9 * 9 * 3)))
         }
     }
 
-    fn constraint(&self) -> Constraint {
+    fn constraint(self) -> Constraint {
         match self.0 {
             0 => Constraint::Cell,
             1 => Constraint::Row,
@@ -149,7 +163,7 @@ impl DenseColumn {
 
     /// Determines the column of the left neighbor of this DenseColumn when the exact cover sudoku 
     /// matrix is in its "natural" (aka clear) state.
-    pub fn natural_left(&self) -> DenseColumn {
+    pub fn natural_left(self) -> DenseColumn {
         DenseColumn::new(match self.0 {
             0 => 3,
             1 => 0,
@@ -161,7 +175,7 @@ impl DenseColumn {
 
     /// Determines the column of the right neighbor of this DenseColumn when the exact cover sudoku 
     /// matrix is in its "natural" (aka clear) state.
-    pub fn natural_right(&self) -> DenseColumn {
+    pub fn natural_right(self) -> DenseColumn {
         DenseColumn::new(match self.0 {
             0 => 1,
             1 => 2,
@@ -178,20 +192,24 @@ impl SparseColumn {
         SparseColumn(sparse_column_index)
     }
 
-    pub fn to_dense(&self) -> DenseColumn {
+    pub fn index(self) -> usize {
+        self.0
+    }
+
+    pub fn to_dense(self) -> DenseColumn {
         DenseColumn::new(self.0 / (9 * 9))
     }
 
-    pub fn first_row(&self) -> SparseRow {
+    pub fn first_row(self) -> SparseRow {
         // Again, this page is invaluable: http://www.stolaf.edu/people/hansonr/sudoku/exactcovermatrix.htm
         match self.to_dense().constraint() {
             Constraint::Cell => {
-                // Note constraint_offset == self.0
-                let constraint_offset = self.0 - 0 * 9 * 9;
+                // Cell constraints start at sparse column 0, so the offset is the column itself.
+                let constraint_offset = self.0;
                 SparseRow::new(constraint_offset * 9)
             },
             Constraint::Row => {
-                let constraint_offset = self.0 - 1 * 9 * 9;
+                let constraint_offset = self.0 - 9 * 9;
                 SparseRow::new(constraint_offset + (constraint_offset / 9) * 9 * 9 - (constraint_offset / 9) * 9)
             },
             Constraint::Column => {
@@ -258,8 +276,8 @@ mod tests {
             (728, 2, 242),
             (728, 3, 323)
         ];
-        for &(ref dense_row, ref dense_column, ref expected_sparse_column) in &cases {
-            assert_eq!(SparseColumn::new(*expected_sparse_column), DenseColumn::new(*dense_column).to_sparse(&DenseRow::new(*dense_row)));
+        for &(dense_row, dense_column, expected_sparse_column) in &cases {
+            assert_eq!(SparseColumn::new(expected_sparse_column), DenseColumn::new(dense_column).to_sparse(&DenseRow::new(dense_row)));
         }
     }
 
@@ -272,13 +290,11 @@ mod tests {
             (2, 1),
             (3, 2)
         ];
-        for &(ref initial_dense_column, ref expected_dense_column) in &cases {
-            for dense_row_index in 0..9*9*9 {
-                let initial_column = DenseColumn::new(*initial_dense_column);
-                let expected_column = DenseColumn::new(*expected_dense_column);
-                let actual_column = initial_column.natural_left();
-                assert_eq!(expected_column, actual_column);
-            }
+        for &(initial_dense_column, expected_dense_column) in &cases {
+            let initial_column = DenseColumn::new(initial_dense_column);
+            let expected_column = DenseColumn::new(expected_dense_column);
+            let actual_column = initial_column.natural_left();
+            assert_eq!(expected_column, actual_column);
         }
     }
 
@@ -291,9 +307,9 @@ mod tests {
             (2, 3),
             (3, 0)
         ];
-        for &(ref initial_dense_column, ref expected_dense_column) in &cases {
-            let initial_column = DenseColumn::new(*initial_dense_column);
-            let expected_column = DenseColumn::new(*expected_dense_column);
+        for &(initial_dense_column, expected_dense_column) in &cases {
+            let initial_column = DenseColumn::new(initial_dense_column);
+            let expected_column = DenseColumn::new(expected_dense_column);
             let actual_column = initial_column.natural_right();
             assert_eq!(expected_column, actual_column);
         }
@@ -342,10 +358,10 @@ mod tests {
             (188, 3, 179),
             (459, 3, 396),
         ];
-        for &(ref initial_dense_row, ref initial_dense_column, ref expected_dense_row) in &cases {
-            let initial_row = DenseRow::new(*initial_dense_row);
-            let initial_column = DenseColumn::new(*initial_dense_column);
-            let expected_row = DenseRow::new(*expected_dense_row);
+        for &(initial_dense_row, initial_dense_column, expected_dense_row) in &cases {
+            let initial_row = DenseRow::new(initial_dense_row);
+            let initial_column = DenseColumn::new(initial_dense_column);
+            let expected_row = DenseRow::new(expected_dense_row);
             let actual_row = initial_row.natural_up(&initial_column);
             assert_eq!(expected_row, actual_row);
         }
@@ -397,10 +413,10 @@ mod tests {
             (720, 3, 540),
             (728, 3, 548)
         ];
-        for &(ref initial_dense_row, ref initial_dense_column, ref expected_dense_row) in &cases {
-            let initial_row = DenseRow::new(*initial_dense_row);
-            let initial_column = DenseColumn::new(*initial_dense_column);
-            let expected_row = DenseRow::new(*expected_dense_row);
+        for &(initial_dense_row, initial_dense_column, expected_dense_row) in &cases {
+            let initial_row = DenseRow::new(initial_dense_row);
+            let initial_column = DenseColumn::new(initial_dense_column);
+            let expected_row = DenseRow::new(expected_dense_row);
             let actual_row = initial_row.natural_down(&initial_column);
             assert_eq!(expected_row, actual_row);
         }
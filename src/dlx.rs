@@ -0,0 +1,386 @@
+use board::{Board, Possibility};
+use coords::*;
+use {Solve, SolveError};
+
+// 729 possibilities, aka rows in the exact cover matrix. The number comes from
+// 9 * 9 cells on the board, each of which can have one of 9 numbers.
+const POSSIBILITIES: usize = 9 * 9 * 9;
+// 324 constraints, aka columns in the exact cover matrix. There are 9 cell
+// constraints, 9 row constraints, 9 column constraints, and 9 box constraints,
+// each of which consist of 9 numbers.
+const CONSTRAINTS: usize = 9 * 9 + 9 * 9 + 9 * 9 + 9 * 9;
+// Each possibility contributes only 4 ones in the exact cover matrix. This fact,
+// combined with the regular nature of the sudoku exact cover matrix, allows us to
+// represent the sparse exact cover matrix in a space-efficient dense representation.
+const NONZERO_CONSTRAINTS_PER_POSSIBILITY: usize = 4;
+// A solution never has more rows than there are cells on the board.
+const MAX_SOLUTION_ROWS: usize = 9 * 9;
+
+/// A dancing links solver specialized for 9x9 Sudoku. Unlike the generic `Matrix`, the
+/// exact cover matrix is stored in fixed-size arrays laid out by the `coords` module, so
+/// solving never allocates and a single solver can be reused for any number of boards.
+pub struct DLXSolver {
+    // See http://www.stolaf.edu/people/hansonr/sudoku/exactcovermatrix.htm for the full
+    // expanded exact cover matrix.
+    matrix: [[Link; NONZERO_CONSTRAINTS_PER_POSSIBILITY]; POSSIBILITIES],
+    headers: [Header; CONSTRAINTS],
+    // There is no root header, so instead we remember one uncovered column to start header
+    // traversals from. Only meaningful while uncovered_columns > 0.
+    first_column: SparseColumn,
+    // The number of columns still linked into the header list.
+    uncovered_columns: usize,
+    // Matrix rows chosen by the search so far, of which the first solution_len are valid.
+    solution: [usize; MAX_SOLUTION_ROWS],
+    solution_len: usize
+}
+
+#[derive(Debug, Default, Clone, Copy)]
+struct Link {
+    // Going up or down involves a change in row.
+    up: DenseRow,
+    // Going up or down involves a change in row.
+    down: DenseRow,
+    // Going left or right involves a change in column.
+    left: DenseColumn,
+    // Going left or right involves a change in column.
+    right: DenseColumn
+}
+
+#[derive(Debug, Default, Clone, Copy)]
+struct Header {
+    // The number of remaining 1 entries in this column in the exact cover matrix.
+    column_size: usize,
+    // Which column this header is for.
+    column_index: SparseColumn,
+    // Some row still linked into this column. Columns have no header node of their own in
+    // the dense layout, so this is where traversals of the column start. Only meaningful
+    // while column_size > 0.
+    first_row: DenseRow,
+    // Going left or right involves a change in column.
+    left: SparseColumn,
+    // Going left or right involves a change in column.
+    right: SparseColumn
+}
+
+impl DLXSolver {
+    pub fn new() -> DLXSolver {
+        // Allocate memory for matrix and headers.
+        let matrix = [[Link::default(); NONZERO_CONSTRAINTS_PER_POSSIBILITY]; POSSIBILITIES];
+        let headers = [Header::default(); CONSTRAINTS];
+
+        // Initialize a cleared DLXSolver.
+        let mut solver = DLXSolver {
+            matrix,
+            headers,
+            first_column: SparseColumn::default(),
+            uncovered_columns: 0,
+            solution: [0; MAX_SOLUTION_ROWS],
+            solution_len: 0
+        };
+        solver.clear();
+
+        solver
+    }
+
+    fn clear(&mut self) {
+        // Set up header links.
+        for column_index in 0..CONSTRAINTS {
+            let sparse_column_index = SparseColumn::new(column_index);
+            let header = &mut self.headers[column_index];
+            header.column_size = 9;
+            header.column_index = sparse_column_index;
+            header.left = SparseColumn::new(if column_index == 0 { CONSTRAINTS - 1 } else { column_index - 1 });
+            header.right = SparseColumn::new(if column_index == CONSTRAINTS - 1 { 0 } else { column_index + 1 });
+            header.first_row = sparse_column_index.first_row().to_dense(&sparse_column_index);
+        }
+        self.first_column = SparseColumn::new(0);
+        self.uncovered_columns = CONSTRAINTS;
+
+        // Set up matrix links.
+        for row_index in 0..POSSIBILITIES {
+            let row: &mut [Link] = &mut self.matrix[row_index];
+            // Sparse and dense rows have same value.
+            let dense_row = DenseRow::new(row_index);
+
+            reset_link(row, &dense_row, 0);
+            reset_link(row, &dense_row, 1);
+            reset_link(row, &dense_row, 2);
+            reset_link(row, &dense_row, 3);
+        }
+
+        self.solution_len = 0;
+    }
+
+    fn link(&self, row: DenseRow, column: DenseColumn) -> &Link {
+        &self.matrix[row.index()][column.index()]
+    }
+
+    fn link_mut(&mut self, row: DenseRow, column: DenseColumn) -> &mut Link {
+        &mut self.matrix[row.index()][column.index()]
+    }
+
+    fn header(&self, column: SparseColumn) -> &Header {
+        &self.headers[column.index()]
+    }
+
+    fn header_mut(&mut self, column: SparseColumn) -> &mut Header {
+        &mut self.headers[column.index()]
+    }
+
+    // https://arxiv.org/pdf/cs/0011047.pdf
+    fn cover_column(&mut self, column: SparseColumn) {
+        // Remove this column from the list of headers by making the left and right neighbors point to each other.
+        let Header { left, right, column_size, first_row, .. } = *self.header(column);
+        self.header_mut(right).left = left;
+        self.header_mut(left).right = right;
+        if self.first_column == column {
+            self.first_column = right;
+        }
+        self.uncovered_columns -= 1;
+
+        if column_size == 0 {
+            return;
+        }
+
+        // Go down to every row in this column. Stop once we wrap around to the first row again.
+        let dense_column = column.to_dense();
+        let mut current_row = first_row;
+        loop {
+            // Go right to every link in this row EXCEPT (!) the one in this covered column itself,
+            // and unlink it from its respective column.
+            let mut current_column = self.link(current_row, dense_column).right;
+            while current_column != dense_column {
+                self.unlink_from_column(current_row, current_column);
+                current_column = self.link(current_row, current_column).right;
+            }
+
+            current_row = self.link(current_row, dense_column).down;
+            if current_row == first_row {
+                break;
+            }
+        }
+    }
+
+    // https://arxiv.org/pdf/cs/0011047.pdf
+    // Note the importance of traversing in the opposite order of the cover_column method, ie
+    // up then left instead of down then right
+    fn uncover_column(&mut self, column: SparseColumn) {
+        let Header { left, right, column_size, first_row, .. } = *self.header(column);
+
+        if column_size > 0 {
+            // Go up to every row in this column, starting with the one cover_column visited last.
+            let dense_column = column.to_dense();
+            let mut current_row = self.link(first_row, dense_column).up;
+            loop {
+                let mut current_column = self.link(current_row, dense_column).left;
+                while current_column != dense_column {
+                    self.relink_into_column(current_row, current_column);
+                    current_column = self.link(current_row, current_column).left;
+                }
+
+                if current_row == first_row {
+                    break;
+                }
+                current_row = self.link(current_row, dense_column).up;
+            }
+        }
+
+        // Restore this column to the list of headers by making the left and right neighbors point to this header.
+        self.header_mut(right).left = column;
+        self.header_mut(left).right = column;
+        if self.uncovered_columns == 0 {
+            self.first_column = column;
+        }
+        self.uncovered_columns += 1;
+    }
+
+    fn unlink_from_column(&mut self, row: DenseRow, column: DenseColumn) {
+        let Link { up, down, .. } = *self.link(row, column);
+        self.link_mut(up, column).down = down;
+        self.link_mut(down, column).up = up;
+
+        let header = self.header_mut(column.to_sparse(&row));
+        assert!(header.column_size >= 1,
+            "Tried to unlink a link from a column whose column_size was already 0");
+        header.column_size -= 1;
+        // Make sure the column can still be found from its header.
+        if header.first_row == row {
+            header.first_row = down;
+        }
+    }
+
+    fn relink_into_column(&mut self, row: DenseRow, column: DenseColumn) {
+        let Link { up, down, .. } = *self.link(row, column);
+        self.link_mut(up, column).down = row;
+        self.link_mut(down, column).up = row;
+
+        let header = self.header_mut(column.to_sparse(&row));
+        if header.column_size == 0 {
+            header.first_row = row;
+        }
+        header.column_size += 1;
+    }
+
+    // Covers every column of the given row other than `column`, whose header was used to find the row.
+    fn cover_row(&mut self, row: DenseRow, column: DenseColumn) {
+        let mut current_column = self.link(row, column).right;
+        while current_column != column {
+            self.cover_column(current_column.to_sparse(&row));
+            current_column = self.link(row, current_column).right;
+        }
+    }
+
+    // Undoes `cover_row`.
+    fn uncover_row(&mut self, row: DenseRow, column: DenseColumn) {
+        let mut current_column = self.link(row, column).left;
+        while current_column != column {
+            self.uncover_column(current_column.to_sparse(&row));
+            current_column = self.link(row, current_column).left;
+        }
+    }
+
+    // https://arxiv.org/pdf/cs/0011047.pdf
+    // Returns true if a solution was found, in which case the first solution_len entries of
+    // solution hold its rows. The matrix is left partially covered after a successful search,
+    // which is fine as `Solve::solve` calls `clear` before every search.
+    fn search_first(&mut self) -> bool {
+        // If all columns are covered, then we've found a solution.
+        if self.uncovered_columns == 0 {
+            return true;
+        }
+
+        // Choose the column with the fewest rows remaining in it.
+        let mut min_column = self.first_column;
+        let mut current_column = self.header(self.first_column).right;
+        while current_column != self.first_column {
+            if self.header(current_column).column_size < self.header(min_column).column_size {
+                min_column = current_column;
+            }
+            current_column = self.header(current_column).right;
+        }
+
+        // If we found a column with no rows in it, then there is no exact cover solution.
+        let Header { column_size, first_row, .. } = *self.header(min_column);
+        if column_size == 0 {
+            return false;
+        }
+
+        self.cover_column(min_column);
+
+        // Go through every row in the minimum-sized column and try adding it to the solution.
+        let dense_column = min_column.to_dense();
+        let mut current_row = first_row;
+        loop {
+            self.solution[self.solution_len] = current_row.to_sparse().index();
+            self.solution_len += 1;
+            self.cover_row(current_row, dense_column);
+
+            if self.search_first() {
+                return true;
+            }
+
+            self.solution_len -= 1;
+            self.uncover_row(current_row, dense_column);
+
+            current_row = self.link(current_row, dense_column).down;
+            if current_row == first_row {
+                break;
+            }
+        }
+
+        self.uncover_column(min_column);
+
+        false
+    }
+}
+
+impl Default for DLXSolver {
+    fn default() -> DLXSolver {
+        DLXSolver::new()
+    }
+}
+
+fn reset_link(row: &mut [Link], dense_row: &DenseRow, dense_column_index: usize) {
+    let constraint: &mut Link = &mut row[dense_column_index];
+    let dense_column = DenseColumn::new(dense_column_index);
+    constraint.left = dense_column.natural_left();
+    constraint.right = dense_column.natural_right();
+    constraint.up = dense_row.natural_up(&dense_column);
+    constraint.down = dense_row.natural_down(&dense_column);
+}
+
+impl Solve for DLXSolver {
+    fn solve(&mut self, board: &mut Board) -> Result<(), SolveError> {
+        board.check_givens()?;
+        self.clear();
+
+        // Remove options from the full exact cover matrix by covering columns that correspond to
+        // possibilities that are already known.
+        for row in 0..9 {
+            for column in 0..9 {
+                if let Some(number) = board.get_entry(row, column) {
+                    let dense_row = DenseRow::new(Possibility::new(row, column, number).get_matrix_row());
+                    for dense_column_index in 0..NONZERO_CONSTRAINTS_PER_POSSIBILITY {
+                        self.cover_column(DenseColumn::new(dense_column_index).to_sparse(&dense_row));
+                    }
+                }
+            }
+        }
+
+        if !self.search_first() {
+            return Err(SolveError::NoSolution);
+        }
+        board.apply_solution_rows(&self.solution[..self.solution_len]);
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use board::Board;
+    use {Solve, SolveError};
+    use super::DLXSolver;
+
+    #[test]
+    fn solve_matches_matrix_solver() {
+        let puzzles = [
+            "530070000600195000098000060800060003400803001700020006060000280000419005000080079",
+            "000000010400000000020000000000050407008000300001090000300400200050100000000806000",
+            "800000000003600000070090200050007000000045700000100030001000068008500010090000400"
+        ];
+        // A single solver is reused across puzzles to make sure clearing restores it completely.
+        let mut solver = DLXSolver::new();
+        for puzzle in puzzles.iter() {
+            let mut expected = Board::from_singleline_str(puzzle).unwrap();
+            expected.solve().unwrap();
+
+            let mut actual = Board::from_singleline_str(puzzle).unwrap();
+            solver.solve(&mut actual).unwrap();
+            assert_eq!(expected, actual);
+        }
+    }
+
+    #[test]
+    fn solve_rejects_contradictory_givens() {
+        // Covering the same column twice would corrupt the links, so the solver must refuse before searching.
+        let mut board = Board::from_singleline_str("000000000000000000000000000000000000000040000000000000000000000000000000000040000").unwrap();
+        let mut solver = DLXSolver::new();
+        match solver.solve(&mut board) {
+            Err(SolveError::ContradictoryGivens(conflicts)) => assert_eq!(1, conflicts.len()),
+            other => panic!("Unexpected result {:?}", other)
+        }
+
+        // The solver must still work afterwards.
+        let mut board = Board::from_singleline_str("530070000600195000098000060800060003400803001700020006060000280000419005000080079").unwrap();
+        assert_eq!(Ok(()), solver.solve(&mut board));
+    }
+
+    #[test]
+    fn solve_without_solution() {
+        // The top right cell can only be a 9, but there is already a 9 further down its column.
+        let mut board = Board::from_singleline_str("123456780000000000000000000000000000000000009000000000000000000000000000000000000").unwrap();
+        let mut solver = DLXSolver::new();
+        assert_eq!(Err(SolveError::NoSolution), solver.solve(&mut board));
+    }
+}
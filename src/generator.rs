@@ -0,0 +1,302 @@
+use board::Board;
+use rating::Difficulty;
+use rng::Rng;
+use solvers::MatrixSolver;
+
+/// A symmetry which the layout of a puzzle's givens can be required to have: wherever there is a
+/// given, the cells it maps to must also have givens.
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub enum Symmetry {
+    None,
+    /// Unchanged by turning the board half way around.
+    Rotational180,
+    /// Unchanged by turning the board a quarter of the way around.
+    Rotational90,
+    /// Mirrored across the diagonal from the top left to the bottom right.
+    Diagonal,
+    /// Mirrored across the diagonal from the top right to the bottom left.
+    AntiDiagonal,
+    /// Mirrored across the middle row, so the top half matches the bottom half.
+    Horizontal,
+    /// Mirrored across the middle column, so the left half matches the right half.
+    Vertical
+}
+
+impl Symmetry {
+    /// The cells which must all be givens or all be empty together with the given cell, including
+    /// the cell itself.
+    pub fn orbit(self, row: usize, column: usize) -> Vec<(usize, usize)> {
+        let mut orbit = vec![(row, column)];
+        loop {
+            let (row, column) = orbit[orbit.len() - 1];
+            let next = match self {
+                Symmetry::None => (row, column),
+                Symmetry::Rotational180 => (8 - row, 8 - column),
+                Symmetry::Rotational90 => (column, 8 - row),
+                Symmetry::Diagonal => (column, row),
+                Symmetry::AntiDiagonal => (8 - column, 8 - row),
+                Symmetry::Horizontal => (8 - row, column),
+                Symmetry::Vertical => (row, 8 - column)
+            };
+            if orbit.contains(&next) {
+                return orbit;
+            }
+            orbit.push(next);
+        }
+    }
+
+    /// Whether the layout of the board's givens has this symmetry.
+    pub fn holds_for(self, board: &Board) -> bool {
+        board.cells().all(|(row, column, entry)| {
+            self.orbit(row, column).into_iter().all(|(row, column)| board.get_entry(row, column).is_some() == entry.is_some())
+        })
+    }
+}
+
+/// Generates random puzzles with exactly one solution. Everything is driven by the seed, so a
+/// generator created with the same seed produces the same sequence of puzzles on any machine.
+pub struct Generator {
+    rng: Rng,
+    solver: MatrixSolver,
+    symmetry: Symmetry,
+    // Whether puzzles which aren't minimal are thrown away.
+    minimal: bool
+}
+
+impl Generator {
+    pub fn new(seed: u64) -> Generator {
+        Generator {
+            rng: Rng::new(seed),
+            solver: MatrixSolver::new(),
+            symmetry: Symmetry::None,
+            minimal: false
+        }
+    }
+
+    /// Sets whether only minimal puzzles are generated, see `Board::is_minimal`. Removing givens
+    /// one at a time always leaves a minimal puzzle, but constraints on which givens may be
+    /// removed can leave some redundant ones. Puzzles with redundant givens are then discarded
+    /// and count as failed attempts, of which there are many with `Symmetry::Rotational90`.
+    pub fn minimal(mut self, minimal: bool) -> Generator {
+        self.minimal = minimal;
+        self
+    }
+
+    /// Sets the symmetry the layout of the givens must have. Givens are then removed a whole orbit
+    /// at a time, so the puzzles tend to have more of them.
+    pub fn symmetry(mut self, symmetry: Symmetry) -> Generator {
+        self.symmetry = symmetry;
+        self
+    }
+
+    /// Generates a random, completely filled, valid board.
+    pub fn generate_solution(&mut self) -> Board {
+        let mut solution = Board::new();
+        self.solver.solve_random(&mut solution, &mut self.rng)
+            .expect("an empty board always has a solution");
+        solution
+    }
+
+    /// Generates a puzzle with a unique solution. Starting from a random solution, givens are
+    /// removed in a random order, skipping any whose removal would allow a second solution. The
+    /// first attempt always succeeds unless only minimal puzzles are wanted, in which case this
+    /// gives up with None after `attempts` puzzles turn out not to be minimal.
+    pub fn generate(&mut self, attempts: usize) -> Option<Board> {
+        (0..attempts).filter_map(|_| self.try_generate()).next()
+    }
+
+    /// Generates puzzles until one falls within the difficulty band, giving up with None after
+    /// `attempts` puzzles. Harder bands need more attempts, as most random puzzles are easy.
+    /// Puzzles discarded for not being minimal count towards the attempts.
+    pub fn generate_with_difficulty(&mut self, difficulty: &Difficulty, attempts: usize) -> Option<Board> {
+        (0..attempts).filter_map(|_| self.try_generate()).find(|puzzle| difficulty.matches(puzzle))
+    }
+
+    /// Generates a puzzle whose givens are exactly the cells marked true in the mask, indexed by
+    /// row and then column, for example to draw a shape. Random solutions are tried until the
+    /// digits they put in the mask have a unique solution, giving up with None after `attempts`
+    /// solutions. The fewer cells in the mask, the more attempts are needed.
+    pub fn generate_with_mask(&mut self, mask: &[[bool; 9]; 9], attempts: usize) -> Option<Board> {
+        for _ in 0..attempts {
+            let mut puzzle = self.generate_solution();
+            for (row, column) in all_cells() {
+                if !mask[row][column] {
+                    puzzle.clear_entry(row, column);
+                }
+            }
+            if self.solver.count_solutions(&puzzle, 2) == 1 && (!self.minimal || self.solver.is_minimal(&puzzle)) {
+                return Some(puzzle);
+            }
+        }
+        None
+    }
+
+    // Makes a single attempt at a puzzle, which fails if it should be minimal but isn't.
+    fn try_generate(&mut self) -> Option<Board> {
+        let solution = self.generate_solution();
+        let puzzle = self.remove_givens(solution);
+        if !self.minimal || self.solver.is_minimal(&puzzle) {
+            Some(puzzle)
+        } else {
+            None
+        }
+    }
+
+    // Removes as many givens as possible from the solution while keeping it unique, taking away
+    // each symmetric orbit of givens together.
+    fn remove_givens(&mut self, mut puzzle: Board) -> Board {
+        let mut cells = all_cells();
+        self.rng.shuffle(&mut cells);
+        for (row, column) in cells {
+            if puzzle.get_entry(row, column).is_none() {
+                // Already removed along with another cell of its orbit.
+                continue;
+            }
+            let removed: Vec<(usize, usize, usize)> = self.symmetry.orbit(row, column).into_iter()
+                .map(|(row, column)| (row, column, puzzle.get_entry(row, column).unwrap()))
+                .collect();
+            for &(row, column, _) in &removed {
+                puzzle.clear_entry(row, column);
+            }
+            if self.solver.count_solutions(&puzzle, 2) != 1 {
+                for (row, column, number) in removed {
+                    puzzle.set_entry(row, column, number);
+                }
+            }
+        }
+        puzzle
+    }
+}
+
+// Every cell of the board, in row-major order.
+fn all_cells() -> Vec<(usize, usize)> {
+    (0..9).flat_map(|row| (0..9).map(move |column| (row, column))).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use board::Board;
+    use logic::Technique;
+    use rating::Difficulty;
+    use super::{Generator, Symmetry};
+
+    #[test]
+    fn generates_solutions() {
+        let mut generator = Generator::new(1);
+        let first = generator.generate_solution();
+        let second = generator.generate_solution();
+        assert!(first.is_solved());
+        assert!(second.is_solved());
+        assert_ne!(first, second);
+    }
+
+    #[test]
+    fn generates_unique_puzzles() {
+        let mut generator = Generator::new(2);
+        for _ in 0..5 {
+            let puzzle = generator.generate(1).unwrap();
+            assert!(puzzle.has_unique_solution());
+            assert!(!puzzle.is_complete());
+        }
+    }
+
+    #[test]
+    fn generates_minimal_puzzles() {
+        let mut generator = Generator::new(5).minimal(true);
+        for _ in 0..3 {
+            assert!(generator.generate(100).unwrap().is_minimal());
+        }
+        // Removing givens one at a time can't leave a redundant one behind.
+        assert!(Generator::new(5).generate(1).unwrap().is_minimal());
+    }
+
+    #[test]
+    fn minimal_attempts_run_out() {
+        // Quarter turn orbits of four cells rarely leave a minimal puzzle.
+        let mut generator = Generator::new(8).symmetry(Symmetry::Rotational90).minimal(true);
+        assert_eq!(None, generator.generate(1));
+    }
+
+    #[test]
+    fn same_seed_same_puzzles() {
+        let mut first = Generator::new(42);
+        let mut second = Generator::new(42);
+        for _ in 0..3 {
+            assert_eq!(first.generate(1).unwrap(), second.generate(1).unwrap());
+        }
+        assert_ne!(Generator::new(42).generate(1).unwrap(), Generator::new(43).generate(1).unwrap());
+    }
+
+    #[test]
+    fn seed_reproduces_puzzle() {
+        // Pinned so that any change to the sequence of puzzles a seed makes is noticed.
+        let expected = Board::from_singleline_str("000000090032006050000000004400003581000074000600908000008700003100000040070009068").unwrap();
+        assert_eq!(expected, Generator::new(0).generate(1).unwrap());
+    }
+
+    #[test]
+    fn generates_within_difficulty() {
+        let mut generator = Generator::new(3);
+        for difficulty in &[Difficulty::easy(), Difficulty::medium(), Difficulty::hard()] {
+            let puzzle = generator.generate_with_difficulty(difficulty, 100).unwrap();
+            assert!(puzzle.has_unique_solution());
+            assert!(difficulty.matches(&puzzle));
+        }
+
+        let pointing = Difficulty::Techniques {
+            allowed: vec![Technique::HiddenSingle, Technique::NakedSingle, Technique::LockedCandidatesPointing],
+            required: vec![Technique::LockedCandidatesPointing]
+        };
+        assert!(generator.generate_with_difficulty(&pointing, 100).is_some());
+    }
+
+    #[test]
+    fn difficulty_attempts_run_out() {
+        let impossible = Difficulty::Rating { min: 20.0, max: 30.0 };
+        assert_eq!(None, Generator::new(4).generate_with_difficulty(&impossible, 3));
+    }
+
+    #[test]
+    fn orbits() {
+        assert_eq!(vec![(4, 4)], Symmetry::Rotational90.orbit(4, 4));
+        assert_eq!(vec![(0, 1), (1, 8), (8, 7), (7, 0)], Symmetry::Rotational90.orbit(0, 1));
+        assert_eq!(vec![(0, 1), (8, 7)], Symmetry::Rotational180.orbit(0, 1));
+        assert_eq!(vec![(0, 1), (1, 0)], Symmetry::Diagonal.orbit(0, 1));
+        assert_eq!(vec![(0, 1), (7, 8)], Symmetry::AntiDiagonal.orbit(0, 1));
+        assert_eq!(vec![(0, 1), (8, 1)], Symmetry::Horizontal.orbit(0, 1));
+        assert_eq!(vec![(0, 1), (0, 7)], Symmetry::Vertical.orbit(0, 1));
+        assert_eq!(vec![(0, 1)], Symmetry::None.orbit(0, 1));
+    }
+
+    #[test]
+    fn generates_symmetric_puzzles() {
+        for &symmetry in &[Symmetry::Rotational180, Symmetry::Rotational90, Symmetry::Diagonal, Symmetry::AntiDiagonal,
+                           Symmetry::Horizontal, Symmetry::Vertical] {
+            let puzzle = Generator::new(6).symmetry(symmetry).generate(1).unwrap();
+            assert!(symmetry.holds_for(&puzzle), "{:?}", symmetry);
+            assert!(puzzle.has_unique_solution());
+        }
+        let minimal = Generator::new(6).symmetry(Symmetry::Rotational180).minimal(true).generate(100).unwrap();
+        assert!(Symmetry::Rotational180.holds_for(&minimal));
+        assert!(minimal.is_minimal());
+    }
+
+    #[test]
+    fn generates_from_mask() {
+        let layout = Board::from_singleline_str("530070000600195000098000060800060003400803001700020006060000280000419005000080079").unwrap();
+        let mut mask = [[false; 9]; 9];
+        for (row, column, entry) in layout.cells() {
+            mask[row][column] = entry.is_some();
+        }
+        let mut generator = Generator::new(7);
+        let puzzle = generator.generate_with_mask(&mask, 1000).unwrap();
+        assert!(puzzle.has_unique_solution());
+        assert!(puzzle.cells().all(|(row, column, entry)| entry.is_some() == mask[row][column]));
+        assert_ne!(layout, puzzle);
+
+        // No puzzle with fewer than 17 givens has a unique solution.
+        let mut sparse = [[false; 9]; 9];
+        sparse[0] = [true; 9];
+        assert_eq!(None, generator.generate_with_mask(&sparse, 3));
+    }
+}
@@ -0,0 +1,95 @@
+use board::unit_cells;
+use candidates::{CandidateSet, Candidates};
+use super::{all_units, combinations, eliminations_seeing, sees, Step, Technique};
+
+// Almost locked sets are only looked for up to this many cells, to keep the search cheap.
+const MAX_ALS_CELLS: usize = 4;
+
+// An almost locked set: n cells within one unit which have only n + 1 candidates between them. If
+// any one of those numbers is removed, the rest are locked into the cells.
+struct AlmostLockedSet {
+    cells: Vec<(usize, usize)>,
+    numbers: CandidateSet
+}
+
+impl AlmostLockedSet {
+    // The cells of the set which could hold the number.
+    fn places(&self, candidates: &Candidates, number: usize) -> Vec<(usize, usize)> {
+        self.cells.iter().cloned().filter(|&(row, column)| candidates.is_candidate(row, column, number)).collect()
+    }
+}
+
+fn almost_locked_sets(candidates: &Candidates) -> Vec<AlmostLockedSet> {
+    let mut sets: Vec<AlmostLockedSet> = Vec::new();
+    for (constraint, index) in all_units() {
+        let open: Vec<(usize, usize)> = unit_cells(constraint, index).iter()
+            .cloned()
+            .filter(|&(row, column)| !candidates.get_candidates(row, column).is_empty())
+            .collect();
+        for size in 1..=MAX_ALS_CELLS {
+            for cells in combinations(&open, size) {
+                let numbers = cells.iter().fold(CandidateSet::empty(), |numbers, &(row, column)| {
+                    numbers.union(candidates.get_candidates(row, column))
+                });
+                // A set sharing a row and box would otherwise be found twice.
+                if numbers.len() == size + 1 && !sets.iter().any(|set| set.cells == cells) {
+                    sets.push(AlmostLockedSet { cells, numbers });
+                }
+            }
+        }
+    }
+    sets
+}
+
+// Two almost locked sets A and B share a restricted common candidate x: every place for x in A sees
+// every place for x in B, so at most one of them holds x. The other one is then locked, so for any
+// other number z they have in common, one of the sets must hold z. z can be eliminated from every
+// cell that sees all the places for z in both sets.
+pub fn find_als_xz(candidates: &Candidates) -> Option<Step> {
+    let sets = almost_locked_sets(candidates);
+    for (i, a) in sets.iter().enumerate() {
+        for b in &sets[i + 1..] {
+            if a.cells.iter().any(|cell| b.cells.contains(cell)) {
+                continue;
+            }
+            let common = a.numbers.intersection(b.numbers);
+            for x in common.iter() {
+                let a_places = a.places(candidates, x);
+                let b_places = b.places(candidates, x);
+                if !a_places.iter().all(|&first| b_places.iter().all(|&second| sees(first, second))) {
+                    continue;
+                }
+                for z in common.iter().filter(|&z| z != x) {
+                    let mut places = a.places(candidates, z);
+                    places.extend(b.places(candidates, z));
+                    let eliminations = eliminations_seeing(candidates, z, &places);
+                    if !eliminations.is_empty() {
+                        let mut cells = a.cells.clone();
+                        cells.extend(b.cells.iter().cloned());
+                        return Some(Step {
+                            technique: Technique::AlsXz,
+                            units: vec![],
+                            cells,
+                            placements: vec![],
+                            eliminations,
+                            chain: vec![]
+                        });
+                    }
+                }
+            }
+        }
+    }
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use logic::Technique;
+    use logic::tests::find_and_check;
+
+    #[test]
+    fn als_xz() {
+        let step = find_and_check("600500000300080050000200010004009000005016030000000004080000005010090008070800600", Technique::AlsXz);
+        assert!(step.cells.len() >= 2);
+    }
+}
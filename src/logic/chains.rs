@@ -0,0 +1,296 @@
+use std::collections::VecDeque;
+
+use board::Possibility;
+use candidates::Candidates;
+use super::{alternating_links, bivalue_cells, candidate, conjugate_pairs, eliminations_seeing, sees, Link, LinkKind, Step, Technique};
+
+// Chains are found breadth first, so they are as short as possible, but are cut off at this many
+// cells to keep the search cheap.
+const MAX_CHAIN_CELLS: usize = 16;
+
+// The strong links for a number form a graph whose connected parts can each be colored with two
+// colors, one of which holds the number everywhere and the other nowhere. If two cells of the same
+// color see each other, that color must be the one without the number. Otherwise, any cell which
+// sees both colors can't hold the number. The step's chain is a loop through the eliminating pair:
+// the strong links joining the pair through the coloring, closed by weak links.
+pub fn find_simple_coloring(candidates: &Candidates) -> Option<Step> {
+    for number in 1..10 {
+        let pairs = conjugate_pairs(candidates, number);
+        let mut colored: Vec<(usize, usize)> = Vec::new();
+        for &(start, _) in &pairs {
+            if colored.contains(&start) {
+                continue;
+            }
+
+            // Color the cells connected to start, remembering the index of the cell each was
+            // reached from.
+            let mut cells = vec![(start, false)];
+            let mut parents = vec![None];
+            let mut queue = VecDeque::new();
+            queue.push_back(0);
+            while let Some(index) = queue.pop_front() {
+                let (cell, color) = cells[index];
+                for &(a, b) in &pairs {
+                    let next = if a == cell { b } else if b == cell { a } else { continue };
+                    if cells.iter().any(|&(other, _)| other == next) {
+                        continue;
+                    }
+                    cells.push((next, !color));
+                    parents.push(Some(index));
+                    queue.push_back(cells.len() - 1);
+                }
+            }
+            colored.extend(cells.iter().map(|&(cell, _)| cell));
+
+            let (eliminations, chain) = match color_wrap(&cells) {
+                Some((first, second)) => {
+                    let color = cells[first].1;
+                    let eliminations = cells.iter()
+                        .filter(|&&(_, other_color)| other_color == color)
+                        .map(|&(cell, _)| candidate(cell, number))
+                        .collect();
+                    let mut chain = coloring_path(&cells, &parents, first, second, number);
+                    chain.push(Link { from: candidate(cells[second].0, number), to: candidate(cells[first].0, number), kind: LinkKind::Weak });
+                    (eliminations, chain)
+                },
+                None => {
+                    let eliminations = color_trap(candidates, &cells, number);
+                    let chain = match eliminations.first() {
+                        Some(&elimination) => {
+                            let sees_color = |color: bool| cells.iter()
+                                .position(|&(other, other_color)| other_color == color && sees((elimination.row(), elimination.column()), other))
+                                .unwrap();
+                            let (first, second) = (sees_color(false), sees_color(true));
+                            let mut chain = vec![Link { from: elimination, to: candidate(cells[first].0, number), kind: LinkKind::Weak }];
+                            chain.extend(coloring_path(&cells, &parents, first, second, number));
+                            chain.push(Link { from: candidate(cells[second].0, number), to: elimination, kind: LinkKind::Weak });
+                            chain
+                        },
+                        None => vec![]
+                    };
+                    (eliminations, chain)
+                }
+            };
+            if !eliminations.is_empty() {
+                return Some(Step {
+                    technique: Technique::SimpleColoring,
+                    units: vec![],
+                    cells: cells.iter().map(|&(cell, _)| cell).collect(),
+                    placements: vec![],
+                    eliminations,
+                    chain
+                });
+            }
+        }
+    }
+    None
+}
+
+// If two cells of one color see each other, returns their indices, as every cell of that color
+// loses the number.
+fn color_wrap(cells: &[((usize, usize), bool)]) -> Option<(usize, usize)> {
+    for (first, &(first_cell, color)) in cells.iter().enumerate() {
+        let second = cells.iter().position(|&(second_cell, other_color)| other_color == color && sees(first_cell, second_cell));
+        if let Some(second) = second {
+            return Some((first, second));
+        }
+    }
+    None
+}
+
+// Every uncolored cell which sees cells of both colors loses the number.
+fn color_trap(candidates: &Candidates, cells: &[((usize, usize), bool)], number: usize) -> Vec<Possibility> {
+    eliminations_seeing(candidates, number, &[])
+        .into_iter()
+        .filter(|elimination| {
+            let cell = (elimination.row(), elimination.column());
+            let sees_color = |color: bool| cells.iter().any(|&(other, other_color)| other_color == color && sees(cell, other));
+            sees_color(false) && sees_color(true)
+        })
+        .collect()
+}
+
+// The strong links leading from the first colored cell to the second, going up the coloring
+// towards start until the two cells' routes meet.
+fn coloring_path(cells: &[((usize, usize), bool)], parents: &[Option<usize>], first: usize, second: usize, number: usize) -> Vec<Link> {
+    let ancestors = |mut index: usize| {
+        let mut route = vec![index];
+        while let Some(parent) = parents[index] {
+            route.push(parent);
+            index = parent;
+        }
+        route
+    };
+    let mut up = ancestors(first);
+    let mut down = ancestors(second);
+    // Drop the part of the routes to start which they share, apart from the cell where they meet.
+    while up.len() > 1 && down.len() > 1 && up[up.len() - 2] == down[down.len() - 2] {
+        up.pop();
+        down.pop();
+    }
+    down.pop();
+    up.extend(down.into_iter().rev());
+    up.windows(2)
+        .map(|pair| Link { from: candidate(cells[pair[0]].0, number), to: candidate(cells[pair[1]].0, number), kind: LinkKind::Strong })
+        .collect()
+}
+
+// A chain of cells for one number, alternating between strong and weak links, and starting and
+// ending with a strong link. One of its two ends must hold the number, so it can be eliminated from
+// every cell seeing both ends.
+pub fn find_x_chain(candidates: &Candidates) -> Option<Step> {
+    for number in 1..10 {
+        let pairs = conjugate_pairs(candidates, number);
+        let strong_neighbours = |cell: (usize, usize)| -> Vec<(usize, usize)> {
+            pairs.iter()
+                .filter_map(|&(a, b)| if a == cell { Some(b) } else if b == cell { Some(a) } else { None })
+                .collect()
+        };
+        let places: Vec<(usize, usize)> = eliminations_seeing(candidates, number, &[]).iter()
+            .map(|possibility| (possibility.row(), possibility.column()))
+            .collect();
+
+        for &(start, _) in &pairs {
+            // Each queued chain ends with a strong link.
+            let mut queue: VecDeque<Vec<(usize, usize)>> = strong_neighbours(start).into_iter()
+                .map(|next| vec![start, next])
+                .collect();
+            let mut visited = vec![start];
+            visited.extend(queue.iter().map(|chain| chain[1]));
+            while let Some(chain) = queue.pop_front() {
+                let end = *chain.last().unwrap();
+                // Four or more cells (two strong links joined by a weak one); shorter chains are just locked candidates.
+                if chain.len() >= 4 {
+                    let eliminations: Vec<Possibility> = eliminations_seeing(candidates, number, &[start, end]);
+                    if !eliminations.is_empty() {
+                        let nodes: Vec<Possibility> = chain.iter().map(|&cell| candidate(cell, number)).collect();
+                        return Some(Step {
+                            technique: Technique::XChain,
+                            units: vec![],
+                            cells: chain,
+                            placements: vec![],
+                            eliminations,
+                            chain: alternating_links(&nodes)
+                        });
+                    }
+                }
+                if chain.len() + 2 > MAX_CHAIN_CELLS {
+                    continue;
+                }
+                for &weak in &places {
+                    if !sees(end, weak) || visited.contains(&weak) {
+                        continue;
+                    }
+                    for strong in strong_neighbours(weak) {
+                        if visited.contains(&strong) {
+                            continue;
+                        }
+                        visited.push(weak);
+                        visited.push(strong);
+                        let mut longer = chain.clone();
+                        longer.push(weak);
+                        longer.push(strong);
+                        queue.push_back(longer);
+                    }
+                }
+            }
+        }
+    }
+    None
+}
+
+// A chain of cells with two candidates each, where each cell sees the next and shares a number
+// with it. If the first cell isn't z, each following cell is forced in turn, until the last cell
+// must be z. So z can be eliminated from every cell seeing both ends.
+pub fn find_xy_chain(candidates: &Candidates) -> Option<Step> {
+    let bivalue = bivalue_cells(candidates);
+    for &(start, start_set) in &bivalue {
+        for z in start_set.iter() {
+            let first_exit = start_set.iter().find(|&number| number != z).unwrap();
+            // Each queued chain is its cells along with the numbers linking them, ending with the
+            // number the last cell would be forced to.
+            let mut queue = VecDeque::new();
+            queue.push_back((vec![start], vec![z, first_exit]));
+            let mut visited = vec![start];
+            while let Some((cells, numbers)) = queue.pop_front() {
+                let end = *cells.last().unwrap();
+                let exit = *numbers.last().unwrap();
+                if cells.len() >= 3 && exit == z {
+                    let eliminations = eliminations_seeing(candidates, z, &[start, end]);
+                    if !eliminations.is_empty() {
+                        let mut nodes = Vec::new();
+                        for (i, &cell) in cells.iter().enumerate() {
+                            nodes.push(candidate(cell, numbers[i]));
+                            nodes.push(candidate(cell, numbers[i + 1]));
+                        }
+                        return Some(Step {
+                            technique: Technique::XYChain,
+                            units: vec![],
+                            cells,
+                            placements: vec![],
+                            eliminations,
+                            chain: alternating_links(&nodes)
+                        });
+                    }
+                }
+                if cells.len() >= MAX_CHAIN_CELLS {
+                    continue;
+                }
+                for &(next, next_set) in &bivalue {
+                    if visited.contains(&next) || !sees(end, next) || !next_set.contains(exit) {
+                        continue;
+                    }
+                    visited.push(next);
+                    let mut longer_cells = cells.clone();
+                    longer_cells.push(next);
+                    let mut longer_numbers = numbers.clone();
+                    longer_numbers.push(next_set.iter().find(|&number| number != exit).unwrap());
+                    queue.push_back((longer_cells, longer_numbers));
+                }
+            }
+        }
+    }
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use logic::{LinkKind, Technique};
+    use logic::tests::find_and_check;
+
+    const COLORING: &str = "000001800140000000000006270000000010007300005850000700000410960560900000000007502";
+    const X_CHAIN: &str = "000000030004000000160070040780010000005700400000009002000000006350000080040265700";
+    const XY_CHAIN: &str = "060000809920000000008700000000001960700460500000800042109008020400000030000143000";
+
+    #[test]
+    fn simple_coloring() {
+        let step = find_and_check(COLORING, Technique::SimpleColoring);
+        // A loop through the eliminating pair, whose strong links all follow the coloring.
+        assert!(step.chain.len() >= 3);
+        assert_eq!(step.chain[0].from, step.chain.last().unwrap().to);
+        for pair in step.chain.windows(2) {
+            assert_eq!(pair[0].to, pair[1].from);
+        }
+        for link in step.chain.iter().filter(|link| link.kind == LinkKind::Strong) {
+            assert!(step.cells.contains(&(link.from.row(), link.from.column())));
+            assert!(step.cells.contains(&(link.to.row(), link.to.column())));
+        }
+        assert!(step.chain.iter().any(|link| link.kind == LinkKind::Weak));
+    }
+
+    #[test]
+    fn x_chain() {
+        let step = find_and_check(X_CHAIN, Technique::XChain);
+        assert_eq!(step.cells.len(), step.chain.len() + 1);
+        assert_eq!(LinkKind::Strong, step.chain.last().unwrap().kind);
+    }
+
+    #[test]
+    fn xy_chain() {
+        let step = find_and_check(XY_CHAIN, Technique::XYChain);
+        assert_eq!(2 * step.cells.len() - 1, step.chain.len());
+        let number = step.eliminations[0].number();
+        assert_eq!(number, step.chain[0].from.number());
+        assert_eq!(number, step.chain.last().unwrap().to.number());
+    }
+}
@@ -0,0 +1,231 @@
+use board::{unit_cells, Constraint, Possibility};
+use candidates::{CandidateSet, Candidates};
+use super::{LinkKind, Step, Technique};
+
+impl Step {
+    /// Explains the step in plain English, eg "r5c5 has only one candidate left, 5. Place 5 in
+    /// r5c5." The candidates must be the grid the step was found in, before it was applied.
+    pub fn explain(&self, candidates: &Candidates) -> String {
+        format!("{} {}", self.reason(candidates), self.effect())
+    }
+
+    // Why the step's placements and eliminations follow.
+    fn reason(&self, candidates: &Candidates) -> String {
+        let cells = cell_list(&self.cells);
+        let number = self.placements.first().or_else(|| self.eliminations.first()).map_or(0, |possibility| possibility.number());
+        match self.technique {
+            Technique::NakedSingle =>
+                format!("{} has only one candidate left, {}.", cells, number),
+            Technique::HiddenSingle =>
+                format!("{} is the only place left for {} in {}.", cells, number, unit_name(self.units[0])),
+            Technique::LockedCandidatesPointing | Technique::LockedCandidatesClaiming =>
+                format!("Within {}, {} can only go in {}, so it can't go anywhere else in {}.",
+                    unit_name(self.units[0]), number, cells, unit_name(self.units[1])),
+            Technique::NakedPair | Technique::NakedTriple | Technique::NakedQuad =>
+                format!("{} only have the candidates {} between them, so those numbers can't go anywhere else in {}.",
+                    cells, number_list(self.cell_candidates(candidates)), unit_name(self.units[0])),
+            Technique::HiddenPair | Technique::HiddenTriple | Technique::HiddenQuad => {
+                let removed = self.eliminations.iter().fold(CandidateSet::empty(), |mut removed, elimination| {
+                    removed.insert(elimination.number());
+                    removed
+                });
+                format!("{} can only go in {} within {}, so those cells can't hold anything else.",
+                    number_list(self.cell_candidates(candidates).difference(removed)), cells, unit_name(self.units[0]))
+            },
+            Technique::XWing | Technique::Swordfish | Technique::Jellyfish => {
+                let size = self.units.len() / 2;
+                format!("Within {}, {} can only go in {}, so it can't go anywhere else in those lines.",
+                    unit_list(&self.units[..size]), number, unit_list(&self.units[size..]))
+            },
+            Technique::FinnedXWing | Technique::FinnedSwordfish | Technique::FinnedJellyfish => {
+                let size = self.units.len() / 2;
+                format!("Within {}, {} can only go in {}, apart from some fins in {}. Either a fin is {}, or it can't go anywhere else in those lines.",
+                    unit_list(&self.units[..size]), number, unit_list(&self.units[size..2 * size]),
+                    unit_name(self.units[2 * size]), number)
+            },
+            Technique::XYZWing =>
+                format!("One of {} must be {}, as {} can only be {}, and the other two can each only be {} or one of those.",
+                    cells, number, cell_name(self.cells[0]), number_list(candidates.get_candidates(self.cells[0].0, self.cells[0].1)).replace(" and ", " or "), number),
+            Technique::SimpleColoring =>
+                format!("The strong links for {} between {} split them into two colors, one of which must hold {} everywhere.",
+                    number, cells, number),
+            Technique::XYWing | Technique::WWing | Technique::XChain | Technique::XYChain =>
+                format!("In the chain {}, one of the ends must be {}.", self.chain_notation(), number),
+            Technique::UniqueRectangleType1 | Technique::UniqueRectangleType2 | Technique::UniqueRectangleType3 |
+            Technique::UniqueRectangleType4 =>
+                format!("{} must not end up with just two numbers between them, as they could then be swapped to give a second solution.", cells),
+            Technique::BugPlusOne =>
+                format!("Every other empty cell has two candidates, so unless {} is {} the puzzle would have two solutions.", cells, number),
+            Technique::AlsXz =>
+                format!("{} form two almost locked sets, which can't both lose the number they have in common.", cells),
+            Technique::CellForcingChain =>
+                format!("Whichever candidate {} takes, the same thing follows.", cell_name(self.cells[0])),
+            Technique::UnitForcingChain =>
+                format!("Wherever {} goes in {}, the same thing follows.", self.assumed_number(candidates), unit_name(self.units[0]))
+        }
+    }
+
+    // What the step does, eg "Place 5 in r5c5." or "Remove 3 from r1c1 and r1c2."
+    fn effect(&self) -> String {
+        let mut sentences: Vec<String> = self.placements.iter()
+            .map(|placement| format!("Place {} in {}.", placement.number(), cell_name((placement.row(), placement.column()))))
+            .collect();
+        for number in 1..10 {
+            let cells: Vec<(usize, usize)> = self.eliminations.iter()
+                .filter(|elimination| elimination.number() == number)
+                .map(|elimination| (elimination.row(), elimination.column()))
+                .collect();
+            if !cells.is_empty() {
+                sentences.push(format!("Remove {} from {}.", number, cell_list(&cells)));
+            }
+        }
+        sentences.join(" ")
+    }
+
+    // Every candidate of the step's cells.
+    fn cell_candidates(&self, candidates: &Candidates) -> CandidateSet {
+        self.cells.iter().fold(CandidateSet::empty(), |numbers, &(row, column)| {
+            numbers.union(candidates.get_candidates(row, column))
+        })
+    }
+
+    // The number whose places in its unit a unit forcing chain assumes in turn.
+    fn assumed_number(&self, candidates: &Candidates) -> usize {
+        let (constraint, index) = self.units[0];
+        (1..10).find(|&number| {
+            let places: Vec<(usize, usize)> = unit_cells(constraint, index).iter()
+                .cloned()
+                .filter(|&(row, column)| candidates.is_candidate(row, column, number))
+                .collect();
+            places == self.cells
+        }).unwrap_or(0)
+    }
+
+    // Writes the chain in Eureka notation, eg (5)r1c2=(3)r1c2-(3)r4c2=(5)r4c2.
+    fn chain_notation(&self) -> String {
+        let mut notation = String::new();
+        for (i, link) in self.chain.iter().enumerate() {
+            if i == 0 {
+                notation.push_str(&candidate_name(link.from));
+            }
+            notation.push_str(if link.kind == LinkKind::Strong { "=" } else { "-" });
+            notation.push_str(&candidate_name(link.to));
+        }
+        notation
+    }
+}
+
+fn cell_name(cell: (usize, usize)) -> String {
+    format!("r{}c{}", cell.0 + 1, cell.1 + 1)
+}
+
+fn candidate_name(possibility: Possibility) -> String {
+    format!("({}){}", possibility.number(), cell_name((possibility.row(), possibility.column())))
+}
+
+fn unit_name(unit: (Constraint, usize)) -> String {
+    format!("{} {}", unit.0, unit.1 + 1)
+}
+
+fn cell_list(cells: &[(usize, usize)]) -> String {
+    join(cells.iter().map(|&cell| cell_name(cell)).collect())
+}
+
+fn unit_list(units: &[(Constraint, usize)]) -> String {
+    join(units.iter().map(|&unit| unit_name(unit)).collect())
+}
+
+fn number_list(numbers: CandidateSet) -> String {
+    join(numbers.iter().map(|number| number.to_string()).collect())
+}
+
+// Joins words into an English list, eg "a, b and c".
+fn join(mut words: Vec<String>) -> String {
+    match words.len() {
+        0 => String::new(),
+        1 => words.remove(0),
+        _ => {
+            let last = words.pop().unwrap();
+            format!("{} and {}", words.join(", "), last)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use board::{Board, Constraint, Possibility};
+    use candidates::Candidates;
+    use logic::{Link, LinkKind, LogicalSolver, Step, Technique};
+
+    fn step(technique: Technique) -> Step {
+        Step {
+            technique,
+            units: vec![],
+            cells: vec![],
+            placements: vec![],
+            eliminations: vec![],
+            chain: vec![]
+        }
+    }
+
+    #[test]
+    fn explains_every_step() {
+        let solver = LogicalSolver::with_uniqueness_techniques();
+        for puzzle in &[
+            "000400002150300080000002009900040000700001800000050090000000200007680000609070010",
+            "600500000300080050000200010004009000005016030000000004080000005010090008070800600",
+            "000001800140000000000006270000000010007300005850000700000410960560900000000007502",
+            "060000809920000000008700000000001960700460500000800042109008020400000030000143000"
+        ] {
+            let mut candidates = Candidates::from_board(&Board::from_singleline_str(puzzle).unwrap());
+            while let Some(hint) = solver.hint(&candidates) {
+                assert!(hint.explanation.ends_with('.'), "{}", hint.explanation);
+                assert!(!hint.explanation.contains(" 0"), "{}", hint.explanation);
+                hint.step.apply(&mut candidates);
+            }
+            assert!(candidates.is_complete());
+        }
+    }
+
+    #[test]
+    fn explain_single() {
+        let candidates = Candidates::from_board(&Board::new());
+        let hidden_single = Step {
+            units: vec![(Constraint::Row, 2)],
+            cells: vec![(2, 4)],
+            placements: vec![Possibility::new(2, 4, 7)],
+            ..step(Technique::HiddenSingle)
+        };
+        assert_eq!("r3c5 is the only place left for 7 in row 3. Place 7 in r3c5.", hidden_single.explain(&candidates));
+    }
+
+    #[test]
+    fn explain_eliminations() {
+        let candidates = Candidates::from_board(&Board::new());
+        let pointing = Step {
+            units: vec![(Constraint::Box, 0), (Constraint::Row, 0)],
+            cells: vec![(0, 0), (0, 1)],
+            eliminations: vec![Possibility::new(0, 3, 4), Possibility::new(0, 8, 4)],
+            ..step(Technique::LockedCandidatesPointing)
+        };
+        assert_eq!("Within box 1, 4 can only go in r1c1 and r1c2, so it can't go anywhere else in row 1. Remove 4 from r1c4 and r1c9.",
+            pointing.explain(&candidates));
+    }
+
+    #[test]
+    fn explain_chain() {
+        let candidates = Candidates::from_board(&Board::new());
+        let chain = Step {
+            cells: vec![(0, 0), (0, 5), (4, 5), (4, 0)],
+            eliminations: vec![Possibility::new(2, 1, 1)],
+            chain: vec![
+                Link { from: Possibility::new(0, 0, 1), to: Possibility::new(0, 5, 1), kind: LinkKind::Strong },
+                Link { from: Possibility::new(0, 5, 1), to: Possibility::new(4, 5, 1), kind: LinkKind::Weak },
+                Link { from: Possibility::new(4, 5, 1), to: Possibility::new(4, 0, 1), kind: LinkKind::Strong }
+            ],
+            ..step(Technique::XChain)
+        };
+        assert_eq!("In the chain (1)r1c1=(1)r1c6-(1)r5c6=(1)r5c1, one of the ends must be 1. Remove 1 from r3c2.",
+            chain.explain(&candidates));
+    }
+}
@@ -0,0 +1,143 @@
+use board::{box_of, unit_cells, unit_of, Constraint, Possibility};
+use candidates::Candidates;
+use super::{combinations, Step, Technique};
+
+// If a number's places in `size` rows all lie in the same `size` columns, each of those columns
+// must hold the number in one of those rows, so it can be eliminated from the rest of the columns.
+// The same goes with rows and columns swapped.
+//
+// A finned fish has some extra places, the fins, in the base lines. Either a fin holds the number,
+// or the fish works as normal, so the number can still be eliminated from any cell that is both in
+// a cover line and in the box that holds every fin.
+pub fn find_fish(candidates: &Candidates, technique: Technique, size: usize, finned: bool) -> Option<Step> {
+    for number in 1..10 {
+        for &(base, cover) in &[(Constraint::Row, Constraint::Column), (Constraint::Column, Constraint::Row)] {
+            // The cells of each base line the number could go in.
+            let lines: Vec<(usize, Vec<(usize, usize)>)> = (0..9)
+                .map(|line| (line, line_places(candidates, base, line, number)))
+                .filter(|(_, places)| places.len() >= 2 && (finned || places.len() <= size))
+                .collect();
+
+            for base_lines in combinations(&lines, size) {
+                let cells: Vec<(usize, usize)> = base_lines.iter()
+                    .flat_map(|(_, places)| places.iter().cloned())
+                    .collect();
+                let mut cover_lines: Vec<usize> = cells.iter().map(|&(row, column)| unit_of(cover, row, column)).collect();
+                cover_lines.sort();
+                cover_lines.dedup();
+
+                let step = if !finned && cover_lines.len() == size {
+                    fish_step(candidates, technique, number, base, &base_lines, cover, &cover_lines, cells, None)
+                } else if finned && cover_lines.len() > size {
+                    combinations(&cover_lines, size).into_iter()
+                        .filter_map(|cover_lines| {
+                            let fin_box = fin_box(&cells, cover, &cover_lines)?;
+                            fish_step(candidates, technique, number, base, &base_lines, cover, &cover_lines, cells.clone(), Some(fin_box))
+                        })
+                        .next()
+                } else {
+                    None
+                };
+                if step.is_some() {
+                    return step;
+                }
+            }
+        }
+    }
+    None
+}
+
+// The cells of a unit which could hold the number.
+fn line_places(candidates: &Candidates, constraint: Constraint, index: usize, number: usize) -> Vec<(usize, usize)> {
+    unit_cells(constraint, index).iter()
+        .cloned()
+        .filter(|&(row, column)| candidates.is_candidate(row, column, number))
+        .collect()
+}
+
+// The box holding every cell outside the cover lines, if there is one.
+fn fin_box(cells: &[(usize, usize)], cover: Constraint, cover_lines: &[usize]) -> Option<usize> {
+    let mut fins = cells.iter().filter(|&&(row, column)| !cover_lines.contains(&unit_of(cover, row, column)));
+    let &(row, column) = fins.next()?;
+    let fin_box = box_of(row, column);
+    if fins.all(|&(row, column)| box_of(row, column) == fin_box) { Some(fin_box) } else { None }
+}
+
+#[allow(clippy::too_many_arguments)]
+fn fish_step(candidates: &Candidates, technique: Technique, number: usize, base: Constraint,
+    base_lines: &[(usize, Vec<(usize, usize)>)], cover: Constraint, cover_lines: &[usize],
+    cells: Vec<(usize, usize)>, fin_box: Option<usize>) -> Option<Step>
+{
+    let mut eliminations = Vec::new();
+    for &cover_line in cover_lines {
+        for (row, column) in line_places(candidates, cover, cover_line, number) {
+            let in_base = base_lines.iter().any(|&(line, _)| unit_of(base, row, column) == line);
+            let sees_fins = fin_box.is_none_or(|fin_box| box_of(row, column) == fin_box);
+            if !in_base && sees_fins {
+                eliminations.push(Possibility::new(row, column, number));
+            }
+        }
+    }
+    if eliminations.is_empty() {
+        return None;
+    }
+    eliminations.sort();
+
+    let mut units: Vec<(Constraint, usize)> = base_lines.iter().map(|&(line, _)| (base, line)).collect();
+    units.extend(cover_lines.iter().map(|&line| (cover, line)));
+    units.extend(fin_box.map(|fin_box| (Constraint::Box, fin_box)));
+    Some(Step {
+        technique,
+        units,
+        cells,
+        placements: vec![],
+        eliminations,
+        chain: vec![]
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use board::Constraint;
+    use logic::Technique;
+    use logic::tests::find_and_check;
+
+    #[test]
+    fn x_wing() {
+        let step = find_and_check("100000569492056108056109240009640801064010000218035604040500016905061402621000005", Technique::XWing);
+        assert_eq!(4, step.cells.len());
+        assert_eq!(4, step.units.len());
+    }
+
+    #[test]
+    fn swordfish() {
+        let step = find_and_check("008090100060500020000006000030107050000000009004000300050000200070003080200700004", Technique::Swordfish);
+        assert_eq!(6, step.units.len());
+    }
+
+    #[test]
+    fn finned_x_wing() {
+        let step = find_and_check("000000010400000000020000000000050407008000300001090000300400200050100000000806000", Technique::FinnedXWing);
+        assert_eq!(Some(&Constraint::Box), step.units.last().map(|unit| &unit.0));
+    }
+
+    #[test]
+    fn jellyfish() {
+        let step = find_and_check("000070005506000070200500608000200000603409000070000400000000002400805006001003840", Technique::Jellyfish);
+        assert_eq!(8, step.units.len());
+    }
+
+    #[test]
+    fn finned_swordfish() {
+        let step = find_and_check("000067000063900200590800000205001003040000001000002050004009830006050070900000060", Technique::FinnedSwordfish);
+        assert_eq!(7, step.units.len());
+        assert_eq!(Some(&Constraint::Box), step.units.last().map(|unit| &unit.0));
+    }
+
+    #[test]
+    fn finned_jellyfish() {
+        let step = find_and_check("070000400080900006060387000500008060000024700400690800000000603002000000043000201", Technique::FinnedJellyfish);
+        assert_eq!(9, step.units.len());
+        assert_eq!(Some(&Constraint::Box), step.units.last().map(|unit| &unit.0));
+    }
+}
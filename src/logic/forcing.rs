@@ -0,0 +1,141 @@
+use board::{unit_cells, Constraint, Possibility};
+use candidates::Candidates;
+use super::{all_units, Step, Technique};
+use super::singles::{find_hidden_single, find_naked_single};
+
+// Forcing chains only branch on cells or units with at most this many options, as each option
+// needs its own propagation.
+const MAX_BRANCHES: usize = 3;
+
+// One of a cell's candidates must be true. Whatever follows from every one of them, by placing
+// singles, must be true too.
+pub fn find_cell_forcing_chain(candidates: &Candidates) -> Option<Step> {
+    for row in 0..9 {
+        for column in 0..9 {
+            let set = candidates.get_candidates(row, column);
+            if set.len() < 2 || set.len() > MAX_BRANCHES {
+                continue;
+            }
+            let assumptions: Vec<Possibility> = set.iter().map(|number| Possibility::new(row, column, number)).collect();
+            if let Some(step) = common_consequences(candidates, Technique::CellForcingChain, vec![], &assumptions) {
+                return Some(step);
+            }
+        }
+    }
+    None
+}
+
+// A number must go in one of its places in a unit. Whatever follows from every one of them, by
+// placing singles, must be true too.
+pub fn find_unit_forcing_chain(candidates: &Candidates) -> Option<Step> {
+    for (constraint, index) in all_units() {
+        for number in 1..10 {
+            let assumptions: Vec<Possibility> = unit_cells(constraint, index).iter()
+                .filter(|&&(row, column)| candidates.is_candidate(row, column, number))
+                .map(|&(row, column)| Possibility::new(row, column, number))
+                .collect();
+            if assumptions.len() < 2 || assumptions.len() > MAX_BRANCHES {
+                continue;
+            }
+            if let Some(step) = common_consequences(candidates, Technique::UnitForcingChain, vec![(constraint, index)], &assumptions) {
+                return Some(step);
+            }
+        }
+    }
+    None
+}
+
+// Finds the placements, or failing that the eliminations, which follow from every assumption that
+// doesn't lead to a contradiction.
+fn common_consequences(candidates: &Candidates, technique: Technique, units: Vec<(Constraint, usize)>, assumptions: &[Possibility]) -> Option<Step> {
+    let branches: Vec<Candidates> = assumptions.iter().filter_map(|&assumption| propagate(candidates, assumption)).collect();
+    if branches.is_empty() {
+        // The grid itself is contradictory.
+        return None;
+    }
+
+    // Only the first common placement is kept. The rest usually follow from it anyway, and
+    // revealing them all at once would give away most of the solution.
+    let placements: Vec<Possibility> = (0..9 * 9)
+        .map(|i| (i / 9, i % 9))
+        .filter(|&(row, column)| candidates.get_entry(row, column).is_none())
+        .filter_map(|(row, column)| {
+            let number = branches[0].get_entry(row, column)?;
+            if branches.iter().all(|branch| branch.get_entry(row, column) == Some(number)) {
+                Some(Possibility::new(row, column, number))
+            } else {
+                None
+            }
+        })
+        .take(1)
+        .collect();
+    let eliminations: Vec<Possibility> = if placements.is_empty() {
+        candidates.possibilities()
+            .filter(|possibility| branches.iter().all(|branch| {
+                let (row, column, number) = (possibility.row(), possibility.column(), possibility.number());
+                !branch.is_candidate(row, column, number) && branch.get_entry(row, column) != Some(number)
+            }))
+            .collect()
+    } else {
+        vec![]
+    };
+    if placements.is_empty() && eliminations.is_empty() {
+        return None;
+    }
+
+    Some(Step {
+        technique,
+        units,
+        cells: assumptions.iter().map(|assumption| (assumption.row(), assumption.column())).collect(),
+        placements,
+        eliminations,
+        chain: vec![]
+    })
+}
+
+// Places the assumed number and then singles until none are left, returning the resulting grid, or
+// None if it contradicts itself along the way.
+fn propagate(candidates: &Candidates, assumption: Possibility) -> Option<Candidates> {
+    let mut branch = candidates.clone();
+    branch.place(assumption.row(), assumption.column(), assumption.number());
+    loop {
+        if is_contradictory(&branch) {
+            return None;
+        }
+        match find_naked_single(&branch).or_else(|| find_hidden_single(&branch)) {
+            Some(step) => step.apply(&mut branch),
+            None => return Some(branch)
+        }
+    }
+}
+
+// Whether some cell has no candidates left, or some number has nowhere left to go in a unit.
+fn is_contradictory(candidates: &Candidates) -> bool {
+    candidates.has_empty_cell() || all_units().any(|(constraint, index)| {
+        let cells = unit_cells(constraint, index);
+        (1..10).any(|number| cells.iter().all(|&(row, column)| {
+            candidates.get_entry(row, column) != Some(number) && !candidates.is_candidate(row, column, number)
+        }))
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use logic::Technique;
+    use logic::tests::find_and_check;
+
+    const FORCING: &str = "600500000300080050000200010004009000005016030000000004080000005010090008070800600";
+
+    #[test]
+    fn cell_forcing_chain() {
+        let step = find_and_check(FORCING, Technique::CellForcingChain);
+        assert!(step.cells.len() >= 2);
+        assert!(step.cells.iter().all(|&cell| cell == step.cells[0]));
+    }
+
+    #[test]
+    fn unit_forcing_chain() {
+        let step = find_and_check(FORCING, Technique::UnitForcingChain);
+        assert_eq!(1, step.units.len());
+    }
+}
@@ -0,0 +1,88 @@
+use board::{unit_cells, unit_of, Constraint, Possibility};
+use candidates::Candidates;
+use super::{Step, Technique};
+
+// If every place for a number within a box lies on one row or column, the number must go in that
+// part of the line, so it can be eliminated from the rest of the line.
+pub fn find_pointing(candidates: &Candidates) -> Option<Step> {
+    for index in 0..9 {
+        for &line in &[Constraint::Row, Constraint::Column] {
+            if let Some(step) = find_locked(candidates, Technique::LockedCandidatesPointing, Constraint::Box, index, line) {
+                return Some(step);
+            }
+        }
+    }
+    None
+}
+
+// If every place for a number within a row or column lies in one box, the number must go in that
+// part of the box, so it can be eliminated from the rest of the box.
+pub fn find_claiming(candidates: &Candidates) -> Option<Step> {
+    for &line in &[Constraint::Row, Constraint::Column] {
+        for index in 0..9 {
+            if let Some(step) = find_locked(candidates, Technique::LockedCandidatesClaiming, line, index, Constraint::Box) {
+                return Some(step);
+            }
+        }
+    }
+    None
+}
+
+// Looks for a number whose places within the `constraint` unit all lie in a single `target` unit,
+// and which has other places in the target unit to eliminate.
+fn find_locked(candidates: &Candidates, technique: Technique, constraint: Constraint, index: usize, target: Constraint) -> Option<Step> {
+    for number in 1..10 {
+        let cells: Vec<(usize, usize)> = unit_cells(constraint, index).iter()
+            .cloned()
+            .filter(|&(row, column)| candidates.is_candidate(row, column, number))
+            .collect();
+        if cells.len() < 2 {
+            continue;
+        }
+        let target_index = unit_of(target, cells[0].0, cells[0].1);
+        if cells.iter().any(|&(row, column)| unit_of(target, row, column) != target_index) {
+            continue;
+        }
+
+        let eliminations: Vec<Possibility> = unit_cells(target, target_index).iter()
+            .filter(|&&(row, column)| unit_of(constraint, row, column) != index && candidates.is_candidate(row, column, number))
+            .map(|&(row, column)| Possibility::new(row, column, number))
+            .collect();
+        if !eliminations.is_empty() {
+            return Some(Step {
+                technique,
+                units: vec![(constraint, index), (target, target_index)],
+                cells,
+                placements: vec![],
+                eliminations,
+                chain: vec![]
+            });
+        }
+    }
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use board::Constraint;
+    use logic::Technique;
+    use logic::tests::find_and_check;
+
+    #[test]
+    fn pointing() {
+        let step = find_and_check("000000010400000000020000000000050407008000300001090000300400200050100000000806000", Technique::LockedCandidatesPointing);
+        assert_eq!(Constraint::Box, step.units[0].0);
+        let number = step.eliminations[0].number();
+        for elimination in &step.eliminations {
+            assert_eq!(number, elimination.number());
+            assert!(!step.cells.contains(&(elimination.row(), elimination.column())));
+        }
+    }
+
+    #[test]
+    fn claiming() {
+        let step = find_and_check("000000010400000000020000000000050407008000300001090000300400200050100000000806000", Technique::LockedCandidatesClaiming);
+        assert_eq!(Constraint::Box, step.units[1].0);
+        assert!(step.cells.len() >= 2);
+    }
+}
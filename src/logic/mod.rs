@@ -0,0 +1,579 @@
+mod singles;
+mod intersections;
+mod subsets;
+mod fish;
+mod wings;
+mod chains;
+mod uniqueness;
+mod als;
+mod forcing;
+mod explain;
+mod trace;
+
+use std::fmt::{Display, Formatter};
+
+use board::{box_of, unit_cells, Board, Constraint, Possibility};
+use candidates::{CandidateSet, Candidates};
+use {Solve, SolveError};
+
+pub use self::trace::Trace;
+
+/// A human-style deduction rule that the `LogicalSolver` can apply to a candidate grid.
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Hash)]
+pub enum Technique {
+    /// A cell has only one candidate left.
+    NakedSingle,
+    /// A number has only one possible cell left within a row, column, or box.
+    HiddenSingle,
+    /// A number's places within a box all lie on one row or column, so it can be eliminated from
+    /// the rest of that line.
+    LockedCandidatesPointing,
+    /// A number's places within a row or column all lie in one box, so it can be eliminated from
+    /// the rest of that box.
+    LockedCandidatesClaiming,
+    /// Two cells in a unit have the same two candidates, which can be eliminated from the rest of the unit.
+    NakedPair,
+    /// Three cells in a unit have only three candidates between them.
+    NakedTriple,
+    /// Four cells in a unit have only four candidates between them.
+    NakedQuad,
+    /// Two numbers can only go in the same two cells of a unit, so those cells can't hold anything else.
+    HiddenPair,
+    /// Three numbers can only go in the same three cells of a unit.
+    HiddenTriple,
+    /// Four numbers can only go in the same four cells of a unit.
+    HiddenQuad,
+    /// A number's places in two rows lie in the same two columns, or vice versa.
+    XWing,
+    /// A number's places in three rows lie in the same three columns, or vice versa.
+    Swordfish,
+    /// A number's places in four rows lie in the same four columns, or vice versa.
+    Jellyfish,
+    /// An X-Wing with extra places for the number, all in one box.
+    FinnedXWing,
+    /// A Swordfish with extra places for the number, all in one box.
+    FinnedSwordfish,
+    /// A Jellyfish with extra places for the number, all in one box.
+    FinnedJellyfish,
+    /// A cell with candidates xy sees cells with candidates xz and yz, so z can be eliminated from
+    /// cells seeing both of those.
+    XYWing,
+    /// A cell with candidates xyz sees cells with candidates xz and yz, so z can be eliminated from
+    /// cells seeing all three.
+    XYZWing,
+    /// Two cells with candidates xy are joined by a strong link on x, so y can be eliminated from
+    /// cells seeing both.
+    WWing,
+    /// Two-coloring the strong links for a number shows that some candidates can't be true.
+    SimpleColoring,
+    /// An alternating chain of strong and weak links for a single number.
+    XChain,
+    /// A chain of cells with two candidates each, where each cell sees the next.
+    XYChain,
+    /// Three corners of a rectangle have only the candidates ab, so the fourth can't be a or b.
+    /// Assumes a unique solution.
+    UniqueRectangleType1,
+    /// Two corners of a rectangle have only the candidates ab, and the other two abc, so c can be
+    /// eliminated from cells seeing both. Assumes a unique solution.
+    UniqueRectangleType2,
+    /// The extra candidates of a rectangle's two non-ab corners form a naked subset with other cells.
+    /// Assumes a unique solution.
+    UniqueRectangleType3,
+    /// A rectangle's two non-ab corners are the only places for a in a unit, so neither can be b.
+    /// Assumes a unique solution.
+    UniqueRectangleType4,
+    /// Every empty cell has two candidates but one, which must hold its third. Assumes a unique solution.
+    BugPlusOne,
+    /// Two almost locked sets joined by a restricted common candidate.
+    AlsXz,
+    /// Every candidate of a cell leads to the same conclusion.
+    CellForcingChain,
+    /// Every place for a number in a unit leads to the same conclusion.
+    UnitForcingChain
+}
+
+/// A single deduction made by the `LogicalSolver`, with enough detail to explain it.
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct Step {
+    pub technique: Technique,
+    /// The rows, columns, and boxes the pattern was found in, if the technique works on units.
+    pub units: Vec<(Constraint, usize)>,
+    /// The (row, column) coordinates of the cells making up the pattern.
+    pub cells: Vec<(usize, usize)>,
+    /// Numbers placed by this step.
+    pub placements: Vec<Possibility>,
+    /// Candidates removed by this step, not counting those removed as a side effect of placements.
+    pub eliminations: Vec<Possibility>,
+    /// The links between candidates that justify the step, for chain and wing techniques.
+    pub chain: Vec<Link>
+}
+
+/// A logical relationship between two candidates in a chain.
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Hash)]
+pub struct Link {
+    pub from: Possibility,
+    pub to: Possibility,
+    pub kind: LinkKind
+}
+
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Hash)]
+pub enum LinkKind {
+    /// At least one of the two candidates is true, so if one is false the other is true.
+    Strong,
+    /// At most one of the two candidates is true, so if one is true the other is false.
+    Weak
+}
+
+/// The simplest step that makes progress on a grid, along with an explanation for the player.
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct Hint {
+    /// The cells to highlight are `step.cells`, and the resulting changes are its placements and
+    /// eliminations.
+    pub step: Step,
+    pub explanation: String
+}
+
+/// Solves puzzles the way a person would, by repeatedly looking for the simplest technique that
+/// makes progress on the candidate grid. Unlike the exact cover solvers, every deduction is
+/// recorded as a `Step`, so the solve can be explained. Puzzles which need techniques beyond the
+/// solver's repertoire are left partially solved.
+pub struct LogicalSolver {
+    // Tried in order, so simpler techniques should come first.
+    techniques: Vec<Technique>
+}
+
+impl Technique {
+    /// Every technique, from simplest to hardest, ordered by `rating`.
+    pub fn all() -> Vec<Technique> {
+        vec![
+            Technique::HiddenSingle,
+            Technique::NakedSingle,
+            Technique::LockedCandidatesPointing,
+            Technique::LockedCandidatesClaiming,
+            Technique::NakedPair,
+            Technique::XWing,
+            Technique::FinnedXWing,
+            Technique::HiddenPair,
+            Technique::NakedTriple,
+            Technique::Swordfish,
+            Technique::HiddenTriple,
+            Technique::FinnedSwordfish,
+            Technique::XYWing,
+            Technique::XYZWing,
+            Technique::WWing,
+            Technique::UniqueRectangleType1,
+            Technique::UniqueRectangleType2,
+            Technique::UniqueRectangleType3,
+            Technique::UniqueRectangleType4,
+            Technique::SimpleColoring,
+            Technique::NakedQuad,
+            Technique::Jellyfish,
+            Technique::HiddenQuad,
+            Technique::FinnedJellyfish,
+            Technique::BugPlusOne,
+            Technique::XChain,
+            Technique::XYChain,
+            Technique::AlsXz,
+            Technique::CellForcingChain,
+            Technique::UnitForcingChain
+        ]
+    }
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Technique::NakedSingle => "Naked Single",
+            Technique::HiddenSingle => "Hidden Single",
+            Technique::LockedCandidatesPointing => "Locked Candidates (Pointing)",
+            Technique::LockedCandidatesClaiming => "Locked Candidates (Claiming)",
+            Technique::NakedPair => "Naked Pair",
+            Technique::NakedTriple => "Naked Triple",
+            Technique::NakedQuad => "Naked Quad",
+            Technique::HiddenPair => "Hidden Pair",
+            Technique::HiddenTriple => "Hidden Triple",
+            Technique::HiddenQuad => "Hidden Quad",
+            Technique::XWing => "X-Wing",
+            Technique::Swordfish => "Swordfish",
+            Technique::Jellyfish => "Jellyfish",
+            Technique::FinnedXWing => "Finned X-Wing",
+            Technique::FinnedSwordfish => "Finned Swordfish",
+            Technique::FinnedJellyfish => "Finned Jellyfish",
+            Technique::XYWing => "XY-Wing",
+            Technique::XYZWing => "XYZ-Wing",
+            Technique::WWing => "W-Wing",
+            Technique::SimpleColoring => "Simple Coloring",
+            Technique::XChain => "X-Chain",
+            Technique::XYChain => "XY-Chain",
+            Technique::UniqueRectangleType1 => "Unique Rectangle Type 1",
+            Technique::UniqueRectangleType2 => "Unique Rectangle Type 2",
+            Technique::UniqueRectangleType3 => "Unique Rectangle Type 3",
+            Technique::UniqueRectangleType4 => "Unique Rectangle Type 4",
+            Technique::BugPlusOne => "BUG+1",
+            Technique::AlsXz => "ALS-XZ",
+            Technique::CellForcingChain => "Cell Forcing Chain",
+            Technique::UnitForcingChain => "Unit Forcing Chain"
+        }
+    }
+
+    /// How hard the technique is for a person to spot and apply, on a scale similar to Sudoku
+    /// Explainer's. Techniques which Sudoku Explainer doesn't use are slotted in next to similar ones.
+    pub fn rating(self) -> f32 {
+        match self {
+            Technique::HiddenSingle => 1.5,
+            Technique::NakedSingle => 2.3,
+            Technique::LockedCandidatesPointing => 2.6,
+            Technique::LockedCandidatesClaiming => 2.8,
+            Technique::NakedPair => 3.0,
+            Technique::XWing => 3.2,
+            Technique::FinnedXWing => 3.4,
+            Technique::HiddenPair => 3.4,
+            Technique::NakedTriple => 3.6,
+            Technique::Swordfish => 3.8,
+            Technique::HiddenTriple => 4.0,
+            Technique::FinnedSwordfish => 4.0,
+            Technique::XYWing => 4.2,
+            Technique::XYZWing => 4.4,
+            Technique::WWing => 4.4,
+            Technique::UniqueRectangleType1 => 4.5,
+            Technique::UniqueRectangleType2 => 4.6,
+            Technique::UniqueRectangleType3 => 4.6,
+            Technique::UniqueRectangleType4 => 4.6,
+            Technique::SimpleColoring => 4.8,
+            Technique::NakedQuad => 5.0,
+            Technique::Jellyfish => 5.2,
+            Technique::HiddenQuad => 5.4,
+            Technique::FinnedJellyfish => 5.4,
+            Technique::BugPlusOne => 5.6,
+            Technique::XChain => 6.6,
+            Technique::XYChain => 6.8,
+            Technique::AlsXz => 7.0,
+            Technique::CellForcingChain => 8.2,
+            Technique::UnitForcingChain => 8.4
+        }
+    }
+
+    /// Whether the technique is only valid for puzzles with a unique solution. Such techniques
+    /// can make wrong deductions on other puzzles.
+    pub fn assumes_uniqueness(self) -> bool {
+        matches!(self,
+            Technique::UniqueRectangleType1 | Technique::UniqueRectangleType2 | Technique::UniqueRectangleType3 |
+            Technique::UniqueRectangleType4 | Technique::BugPlusOne)
+    }
+
+    /// Looks for the first instance of this technique which makes progress on the grid.
+    pub fn find(self, candidates: &Candidates) -> Option<Step> {
+        match self {
+            Technique::NakedSingle => singles::find_naked_single(candidates),
+            Technique::HiddenSingle => singles::find_hidden_single(candidates),
+            Technique::LockedCandidatesPointing => intersections::find_pointing(candidates),
+            Technique::LockedCandidatesClaiming => intersections::find_claiming(candidates),
+            Technique::NakedPair | Technique::NakedTriple | Technique::NakedQuad =>
+                subsets::find_naked_subset(candidates, self, self.subset_size().unwrap()),
+            Technique::HiddenPair | Technique::HiddenTriple | Technique::HiddenQuad =>
+                subsets::find_hidden_subset(candidates, self, self.subset_size().unwrap()),
+            Technique::XWing | Technique::Swordfish | Technique::Jellyfish =>
+                fish::find_fish(candidates, self, self.subset_size().unwrap(), false),
+            Technique::FinnedXWing | Technique::FinnedSwordfish | Technique::FinnedJellyfish =>
+                fish::find_fish(candidates, self, self.subset_size().unwrap(), true),
+            Technique::XYWing => wings::find_xy_wing(candidates),
+            Technique::XYZWing => wings::find_xyz_wing(candidates),
+            Technique::WWing => wings::find_w_wing(candidates),
+            Technique::SimpleColoring => chains::find_simple_coloring(candidates),
+            Technique::XChain => chains::find_x_chain(candidates),
+            Technique::XYChain => chains::find_xy_chain(candidates),
+            Technique::UniqueRectangleType1 => uniqueness::find_type_1(candidates),
+            Technique::UniqueRectangleType2 => uniqueness::find_type_2(candidates),
+            Technique::UniqueRectangleType3 => uniqueness::find_type_3(candidates),
+            Technique::UniqueRectangleType4 => uniqueness::find_type_4(candidates),
+            Technique::BugPlusOne => uniqueness::find_bug_plus_one(candidates),
+            Technique::AlsXz => als::find_als_xz(candidates),
+            Technique::CellForcingChain => forcing::find_cell_forcing_chain(candidates),
+            Technique::UnitForcingChain => forcing::find_unit_forcing_chain(candidates)
+        }
+    }
+
+    // The number of cells in a naked or hidden subset, or of base lines in a fish.
+    fn subset_size(self) -> Option<usize> {
+        match self {
+            Technique::NakedPair | Technique::HiddenPair | Technique::XWing | Technique::FinnedXWing => Some(2),
+            Technique::NakedTriple | Technique::HiddenTriple | Technique::Swordfish | Technique::FinnedSwordfish => Some(3),
+            Technique::NakedQuad | Technique::HiddenQuad | Technique::Jellyfish | Technique::FinnedJellyfish => Some(4),
+            _ => None
+        }
+    }
+}
+
+impl Display for Technique {
+    fn fmt(&self, f: &mut Formatter) -> Result<(), ::std::fmt::Error> {
+        f.write_str(self.name())
+    }
+}
+
+impl Step {
+    /// Makes this step's placements and eliminations on the grid.
+    pub fn apply(&self, candidates: &mut Candidates) {
+        for placement in &self.placements {
+            candidates.place(placement.row(), placement.column(), placement.number());
+        }
+        for elimination in &self.eliminations {
+            candidates.eliminate(elimination.row(), elimination.column(), elimination.number());
+        }
+    }
+}
+
+impl LogicalSolver {
+    /// Creates a solver that knows every technique which works whether or not the puzzle has a
+    /// unique solution.
+    pub fn new() -> LogicalSolver {
+        let techniques: Vec<Technique> = Technique::all().into_iter()
+            .filter(|technique| !technique.assumes_uniqueness())
+            .collect();
+        LogicalSolver::with_techniques(&techniques)
+    }
+
+    /// Creates a solver that knows every technique, including those that assume the puzzle has a
+    /// unique solution. Only use this for puzzles known to have one, see `Board::has_unique_solution`.
+    pub fn with_uniqueness_techniques() -> LogicalSolver {
+        LogicalSolver::with_techniques(&Technique::all())
+    }
+
+    /// Creates a solver restricted to the given techniques, which are tried in the given order.
+    pub fn with_techniques(techniques: &[Technique]) -> LogicalSolver {
+        LogicalSolver {
+            techniques: techniques.to_vec()
+        }
+    }
+
+    /// Finds the first step made by the first technique that can make progress, without applying it.
+    pub fn next_step(&self, candidates: &Candidates) -> Option<Step> {
+        if candidates.has_empty_cell() {
+            return None;
+        }
+        self.techniques.iter().filter_map(|technique| technique.find(candidates)).next()
+    }
+
+    /// Finds the next step like `next_step`, and explains it.
+    pub fn hint(&self, candidates: &Candidates) -> Option<Hint> {
+        let step = self.next_step(candidates)?;
+        Some(Hint {
+            explanation: step.explain(candidates),
+            step
+        })
+    }
+
+    /// Applies steps until the grid is complete or no technique can make further progress,
+    /// returning every step taken in order.
+    pub fn solve_candidates(&self, candidates: &mut Candidates) -> Vec<Step> {
+        let mut steps = Vec::new();
+        while !candidates.is_complete() {
+            match self.next_step(candidates) {
+                Some(step) => {
+                    step.apply(candidates);
+                    steps.push(step);
+                },
+                None => break
+            }
+        }
+        steps
+    }
+}
+
+impl Default for LogicalSolver {
+    fn default() -> LogicalSolver {
+        LogicalSolver::new()
+    }
+}
+
+impl Solve for LogicalSolver {
+    // Fails with `SolveError::Aborted` if the solver gets stuck before completing the board.
+    fn solve(&mut self, board: &mut Board) -> Result<(), SolveError> {
+        board.check_givens()?;
+        let mut candidates = Candidates::from_board(board);
+        self.solve_candidates(&mut candidates);
+        if !candidates.is_complete() {
+            return Err(if candidates.has_empty_cell() { SolveError::NoSolution } else { SolveError::Aborted });
+        }
+        *board = candidates.to_board();
+        Ok(())
+    }
+}
+
+// Iterates over every row, column, and box, in that order.
+fn all_units() -> impl Iterator<Item = (Constraint, usize)> {
+    [Constraint::Row, Constraint::Column, Constraint::Box].iter()
+        .flat_map(|&constraint| (0..9).map(move |index| (constraint, index)))
+}
+
+// Whether two different cells share a row, column, or box.
+fn sees(a: (usize, usize), b: (usize, usize)) -> bool {
+    a != b && (a.0 == b.0 || a.1 == b.1 || box_of(a.0, a.1) == box_of(b.0, b.1))
+}
+
+// The candidates for a number in every cell which sees all of the given cells.
+fn eliminations_seeing(candidates: &Candidates, number: usize, cells: &[(usize, usize)]) -> Vec<Possibility> {
+    (0..9 * 9)
+        .map(|i| (i / 9, i % 9))
+        .filter(|&(row, column)| candidates.is_candidate(row, column, number))
+        .filter(|&cell| cells.iter().all(|&other| sees(cell, other)))
+        .map(|(row, column)| Possibility::new(row, column, number))
+        .collect()
+}
+
+// The pairs of cells which are the only two places for a number in some unit, each listed once.
+fn conjugate_pairs(candidates: &Candidates, number: usize) -> Vec<((usize, usize), (usize, usize))> {
+    let mut pairs = Vec::new();
+    for (constraint, index) in all_units() {
+        let cells = unit_cells(constraint, index);
+        let mut places = cells.iter()
+            .cloned()
+            .filter(|&(row, column)| candidates.is_candidate(row, column, number));
+        if let (Some(first), Some(second), None) = (places.next(), places.next(), places.next()) {
+            if !pairs.contains(&(first, second)) {
+                pairs.push((first, second));
+            }
+        }
+    }
+    pairs
+}
+
+// The cells with exactly two candidates left, in row-major order.
+fn bivalue_cells(candidates: &Candidates) -> Vec<((usize, usize), CandidateSet)> {
+    (0..9 * 9)
+        .map(|i| ((i / 9, i % 9), candidates.get_candidates(i / 9, i % 9)))
+        .filter(|&(_, set)| set.len() == 2)
+        .collect()
+}
+
+fn candidate(cell: (usize, usize), number: usize) -> Possibility {
+    Possibility::new(cell.0, cell.1, number)
+}
+
+// Links consecutive candidates of an alternating inference chain, starting with a strong link.
+fn alternating_links(nodes: &[Possibility]) -> Vec<Link> {
+    nodes.windows(2)
+        .enumerate()
+        .map(|(i, pair)| Link {
+            from: pair[0],
+            to: pair[1],
+            kind: if i % 2 == 0 { LinkKind::Strong } else { LinkKind::Weak }
+        })
+        .collect()
+}
+
+// Returns every way of choosing `size` of the items, preserving their order.
+fn combinations<T: Clone>(items: &[T], size: usize) -> Vec<Vec<T>> {
+    if size == 0 {
+        return vec![vec![]];
+    }
+    let mut result = Vec::new();
+    for (i, item) in items.iter().enumerate() {
+        for mut rest in combinations(&items[i + 1..], size - 1) {
+            rest.insert(0, item.clone());
+            result.push(rest);
+        }
+    }
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use board::Board;
+    use candidates::Candidates;
+    use {Solve, SolveError};
+    use super::{LogicalSolver, Step, Technique};
+
+    pub const EASY: &str = "530070000600195000098000060800060003400803001700020006060000280000419005000080079";
+
+    // Checks that every placement agrees with the puzzle's unique solution, and every elimination
+    // disagrees with it.
+    pub fn assert_steps_sound(puzzle: &Board, steps: &[Step]) {
+        let mut solution = puzzle.clone();
+        solution.solve_unique().unwrap();
+        for step in steps {
+            for placement in &step.placements {
+                assert_eq!(Some(placement.number()), solution.get_entry(placement.row(), placement.column()),
+                    "{:?} placed a wrong number", step);
+            }
+            for elimination in &step.eliminations {
+                assert!(Some(elimination.number()) != solution.get_entry(elimination.row(), elimination.column()),
+                    "{:?} eliminated the solution", step);
+            }
+        }
+    }
+
+    // Solves the puzzle with every technique until the given technique applies, and checks that
+    // its step is sound.
+    pub fn find_and_check(puzzle: &str, technique: Technique) -> Step {
+        let board = Board::from_singleline_str(puzzle).unwrap();
+        let mut candidates = Candidates::from_board(&board);
+        let solver = LogicalSolver::new();
+        loop {
+            if let Some(step) = technique.find(&candidates) {
+                assert_eq!(technique, step.technique);
+                assert!(!step.placements.is_empty() || !step.eliminations.is_empty());
+                assert_steps_sound(&board, ::std::slice::from_ref(&step));
+                return step;
+            }
+            solver.next_step(&candidates).expect("Technique should apply before the solver gets stuck").apply(&mut candidates);
+        }
+    }
+
+    #[test]
+    fn singles_solve_easy_puzzle() {
+        let board = Board::from_singleline_str(EASY).unwrap();
+        let mut candidates = Candidates::from_board(&board);
+        let steps = LogicalSolver::new().solve_candidates(&mut candidates);
+        assert!(candidates.is_complete());
+        assert_eq!(81 - 30, steps.len());
+        assert_steps_sound(&board, &steps);
+
+        let mut expected = board.clone();
+        expected.solve().unwrap();
+        assert_eq!(expected, candidates.to_board());
+    }
+
+    #[test]
+    fn each_single_alone_solves_easy_puzzle() {
+        for &technique in &[Technique::NakedSingle, Technique::HiddenSingle] {
+            let mut board = Board::from_singleline_str(EASY).unwrap();
+            let mut solver = LogicalSolver::with_techniques(&[technique]);
+            assert_eq!(Ok(()), solver.solve(&mut board));
+            assert!(board.is_solved());
+        }
+    }
+
+    #[test]
+    fn steps_on_hard_puzzles_are_sound() {
+        for puzzle in &[
+            "000000010400000000020000000000050407008000300001090000300400200050100000000806000",
+            "800000000003600000070090200050007000000045700000100030001000068008500010090000400"
+        ] {
+            let board = Board::from_singleline_str(puzzle).unwrap();
+            let mut candidates = Candidates::from_board(&board);
+            let steps = LogicalSolver::new().solve_candidates(&mut candidates);
+            assert_steps_sound(&board, &steps);
+        }
+    }
+
+    #[test]
+    fn techniques_are_ordered_by_rating() {
+        let all = Technique::all();
+        for pair in all.windows(2) {
+            assert!(pair[0].rating() <= pair[1].rating(), "{} is rated higher than {}", pair[0], pair[1]);
+        }
+    }
+
+    #[test]
+    fn uniqueness_techniques_are_opt_in() {
+        assert!(LogicalSolver::new().techniques.iter().all(|technique| !technique.assumes_uniqueness()));
+        assert_eq!(Technique::all(), LogicalSolver::with_uniqueness_techniques().techniques);
+    }
+
+    #[test]
+    fn stuck_solver_aborts() {
+        // Beyond every technique in LogicalSolver::new(), so the solver gets stuck part way.
+        let mut board = Board::from_singleline_str("800000000003600000070090200050007000000045700000100030001000068008500010090000400").unwrap();
+        let original = board.clone();
+        assert_eq!(Err(SolveError::Aborted), LogicalSolver::new().solve(&mut board));
+        assert_eq!(original, board);
+    }
+}
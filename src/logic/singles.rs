@@ -0,0 +1,68 @@
+use board::{unit_cells, Possibility};
+use candidates::Candidates;
+use super::{all_units, Step, Technique};
+
+// A cell with only one candidate left must contain that candidate.
+pub fn find_naked_single(candidates: &Candidates) -> Option<Step> {
+    for row in 0..9 {
+        for column in 0..9 {
+            if let Some(number) = candidates.get_candidates(row, column).single() {
+                return Some(Step {
+                    technique: Technique::NakedSingle,
+                    units: vec![],
+                    cells: vec![(row, column)],
+                    placements: vec![Possibility::new(row, column, number)],
+                    eliminations: vec![],
+                    chain: vec![]
+                });
+            }
+        }
+    }
+    None
+}
+
+// A number with only one possible cell left in a unit must go in that cell.
+pub fn find_hidden_single(candidates: &Candidates) -> Option<Step> {
+    for (constraint, index) in all_units() {
+        let cells = unit_cells(constraint, index);
+        for number in 1..10 {
+            let mut places = cells.iter().filter(|&&(row, column)| candidates.is_candidate(row, column, number));
+            if let (Some(&(row, column)), None) = (places.next(), places.next()) {
+                return Some(Step {
+                    technique: Technique::HiddenSingle,
+                    units: vec![(constraint, index)],
+                    cells: vec![(row, column)],
+                    placements: vec![Possibility::new(row, column, number)],
+                    eliminations: vec![],
+                    chain: vec![]
+                });
+            }
+        }
+    }
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use board::{Constraint, Possibility};
+    use logic::Technique;
+    use logic::tests::{find_and_check, EASY};
+
+    #[test]
+    fn naked_single() {
+        let step = find_and_check(EASY, Technique::NakedSingle);
+        // r5c5 can only be 5, and it is the first such cell in row-major order.
+        assert_eq!(vec![Possibility::new(4, 4, 5)], step.placements);
+        assert_eq!(vec![(4, 4)], step.cells);
+    }
+
+    #[test]
+    fn hidden_single() {
+        let step = find_and_check(EASY, Technique::HiddenSingle);
+        assert_eq!(1, step.placements.len());
+        assert_eq!(1, step.units.len());
+        let (constraint, index) = step.units[0];
+        assert_eq!(Constraint::Row, constraint);
+        assert_eq!(index, step.placements[0].row());
+    }
+}
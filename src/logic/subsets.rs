@@ -0,0 +1,129 @@
+use board::{unit_cells, Possibility};
+use candidates::{CandidateSet, Candidates};
+use super::{all_units, combinations, Step, Technique};
+
+// If `size` cells in a unit have only `size` candidates between them, those numbers must go in
+// those cells, so they can be eliminated from the rest of the unit.
+pub fn find_naked_subset(candidates: &Candidates, technique: Technique, size: usize) -> Option<Step> {
+    for (constraint, index) in all_units() {
+        let cells = unit_cells(constraint, index);
+        let open: Vec<(usize, usize)> = cells.iter()
+            .cloned()
+            .filter(|&(row, column)| {
+                let count = candidates.get_candidates(row, column).len();
+                count >= 2 && count <= size
+            })
+            .collect();
+
+        for subset in combinations(&open, size) {
+            let numbers = subset.iter().fold(CandidateSet::empty(), |numbers, &(row, column)| {
+                numbers.union(candidates.get_candidates(row, column))
+            });
+            if numbers.len() != size {
+                continue;
+            }
+
+            let eliminations: Vec<Possibility> = cells.iter()
+                .filter(|cell| !subset.contains(cell))
+                .flat_map(|&(row, column)| {
+                    candidates.get_candidates(row, column).intersection(numbers).iter()
+                        .map(move |number| Possibility::new(row, column, number))
+                })
+                .collect();
+            if !eliminations.is_empty() {
+                return Some(Step {
+                    technique,
+                    units: vec![(constraint, index)],
+                    cells: subset,
+                    placements: vec![],
+                    eliminations,
+                    chain: vec![]
+                });
+            }
+        }
+    }
+    None
+}
+
+// If `size` numbers can only go in the same `size` cells of a unit, those cells must hold those
+// numbers, so every other candidate can be eliminated from them.
+pub fn find_hidden_subset(candidates: &Candidates, technique: Technique, size: usize) -> Option<Step> {
+    for (constraint, index) in all_units() {
+        let cells = unit_cells(constraint, index);
+        // The cells of the unit each unplaced number could still go in.
+        let places: Vec<(usize, Vec<(usize, usize)>)> = (1..10)
+            .map(|number| {
+                let places = cells.iter()
+                    .cloned()
+                    .filter(|&(row, column)| candidates.is_candidate(row, column, number))
+                    .collect::<Vec<_>>();
+                (number, places)
+            })
+            .filter(|(_, places)| places.len() >= 2 && places.len() <= size)
+            .collect();
+
+        for subset in combinations(&places, size) {
+            let mut numbers = CandidateSet::empty();
+            let mut subset_cells: Vec<(usize, usize)> = Vec::new();
+            for &(number, ref places) in &subset {
+                numbers.insert(number);
+                for &cell in places {
+                    if !subset_cells.contains(&cell) {
+                        subset_cells.push(cell);
+                    }
+                }
+            }
+            if subset_cells.len() != size {
+                continue;
+            }
+            subset_cells.sort();
+
+            let eliminations: Vec<Possibility> = subset_cells.iter()
+                .flat_map(|&(row, column)| {
+                    candidates.get_candidates(row, column).difference(numbers).iter()
+                        .map(move |number| Possibility::new(row, column, number))
+                })
+                .collect();
+            if !eliminations.is_empty() {
+                return Some(Step {
+                    technique,
+                    units: vec![(constraint, index)],
+                    cells: subset_cells,
+                    placements: vec![],
+                    eliminations,
+                    chain: vec![]
+                });
+            }
+        }
+    }
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use logic::Technique;
+    use logic::tests::find_and_check;
+
+    const HARD: &str = "000000010400000000020000000000050407008000300001090000300400200050100000000806000";
+
+    #[test]
+    fn naked_pair() {
+        let step = find_and_check(HARD, Technique::NakedPair);
+        assert_eq!(2, step.cells.len());
+        assert_eq!(1, step.units.len());
+    }
+
+    #[test]
+    fn hidden_pair() {
+        let step = find_and_check(HARD, Technique::HiddenPair);
+        assert_eq!(2, step.cells.len());
+    }
+
+    #[test]
+    fn larger_subsets() {
+        for &technique in &[Technique::NakedTriple, Technique::NakedQuad, Technique::HiddenTriple, Technique::HiddenQuad] {
+            let step = find_and_check(HARD, technique);
+            assert_eq!(technique.subset_size(), Some(step.cells.len()));
+        }
+    }
+}
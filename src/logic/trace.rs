@@ -0,0 +1,202 @@
+use std::fmt::{Display, Formatter, Write};
+
+use board::{Board, Possibility};
+use candidates::Candidates;
+use SolveError;
+use super::{Hint, LinkKind, LogicalSolver};
+
+/// A record of a whole logical solve, for walkthroughs and for checking that solver changes don't
+/// alter explanations.
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct Trace {
+    /// The board the solve started from.
+    pub puzzle: Board,
+    /// Every step taken, in order, each with its explanation.
+    pub steps: Vec<Hint>,
+    /// Whether the steps complete the board. If not, the solver got stuck after the last step.
+    pub solved: bool
+}
+
+impl LogicalSolver {
+    /// Solves the board step by step, recording every step along with its explanation. The board
+    /// itself is left unchanged. Fails if the givens contradict each other, as the pencil marks
+    /// derived from them would lead to nonsense steps.
+    pub fn trace(&self, board: &Board) -> Result<Trace, SolveError> {
+        board.check_givens()?;
+        let mut candidates = Candidates::from_board(board);
+        let mut steps = Vec::new();
+        while !candidates.is_complete() {
+            match self.hint(&candidates) {
+                Some(hint) => {
+                    hint.step.apply(&mut candidates);
+                    steps.push(hint);
+                },
+                None => break
+            }
+        }
+        Ok(Trace {
+            puzzle: board.clone(),
+            steps,
+            solved: candidates.is_complete()
+        })
+    }
+}
+
+impl Trace {
+    /// Serializes the trace as a JSON object. Rows, columns, boxes, and cells are numbered from 0,
+    /// as in the rest of the API, while the explanations number them from 1 like a player would.
+    pub fn to_json(&self) -> String {
+        let mut json = String::new();
+        write!(json, "{{\"puzzle\":\"{}\",\"solved\":{},\"steps\":[", puzzle_line(&self.puzzle), self.solved).unwrap();
+        for (i, hint) in self.steps.iter().enumerate() {
+            if i > 0 {
+                json.push(',');
+            }
+            let step = &hint.step;
+            json.push_str("{\"technique\":");
+            write_string(&mut json, step.technique.name());
+            json.push_str(",\"units\":[");
+            write_list(&mut json, &step.units, |json, &(constraint, index)| {
+                json.push_str("{\"type\":");
+                write_string(json, &constraint.to_string());
+                write!(json, ",\"index\":{}}}", index).unwrap();
+            });
+            json.push_str("],\"cells\":[");
+            write_list(&mut json, &step.cells, |json, &(row, column)| {
+                write!(json, "{{\"row\":{},\"column\":{}}}", row, column).unwrap();
+            });
+            json.push_str("],\"placements\":[");
+            write_list(&mut json, &step.placements, |json, &placement| write_possibility(json, placement));
+            json.push_str("],\"eliminations\":[");
+            write_list(&mut json, &step.eliminations, |json, &elimination| write_possibility(json, elimination));
+            json.push_str("],\"chain\":[");
+            write_list(&mut json, &step.chain, |json, link| {
+                json.push_str("{\"from\":");
+                write_possibility(json, link.from);
+                json.push_str(",\"to\":");
+                write_possibility(json, link.to);
+                let kind = if link.kind == LinkKind::Strong { "strong" } else { "weak" };
+                write!(json, ",\"kind\":\"{}\"}}", kind).unwrap();
+            });
+            json.push_str("],\"explanation\":");
+            write_string(&mut json, &hint.explanation);
+            json.push('}');
+        }
+        json.push_str("]}");
+        json
+    }
+
+    /// Writes the trace as plain text: the puzzle, one numbered line per step, and the outcome.
+    pub fn to_narrative(&self) -> String {
+        self.to_string()
+    }
+}
+
+impl Display for Trace {
+    fn fmt(&self, f: &mut Formatter) -> Result<(), ::std::fmt::Error> {
+        writeln!(f, "Puzzle: {}", puzzle_line(&self.puzzle))?;
+        for (i, hint) in self.steps.iter().enumerate() {
+            writeln!(f, "{}. {}: {}", i + 1, hint.step.technique, hint.explanation)?;
+        }
+        if self.solved {
+            write!(f, "Solved in {} steps.", self.steps.len())
+        } else {
+            write!(f, "Stuck after {} steps.", self.steps.len())
+        }
+    }
+}
+
+// The board as 81 digits, 0 meaning unknown.
+fn puzzle_line(board: &Board) -> String {
+    board.cells().map(|(_, _, entry)| entry.unwrap_or(0).to_string()).collect()
+}
+
+fn write_list<T, F: FnMut(&mut String, &T)>(json: &mut String, items: &[T], mut write_item: F) {
+    for (i, item) in items.iter().enumerate() {
+        if i > 0 {
+            json.push(',');
+        }
+        write_item(json, item);
+    }
+}
+
+fn write_possibility(json: &mut String, possibility: Possibility) {
+    write!(json, "{{\"row\":{},\"column\":{},\"number\":{}}}", possibility.row(), possibility.column(), possibility.number()).unwrap();
+}
+
+fn write_string(json: &mut String, string: &str) {
+    json.push('"');
+    for c in string.chars() {
+        match c {
+            '"' => json.push_str("\\\""),
+            '\\' => json.push_str("\\\\"),
+            '\n' => json.push_str("\\n"),
+            c if (c as u32) < 0x20 => write!(json, "\\u{:04x}", c as u32).unwrap(),
+            c => json.push(c)
+        }
+    }
+    json.push('"');
+}
+
+#[cfg(test)]
+mod tests {
+    use SolveError;
+    use board::{Board, Conflict, Constraint};
+    use logic::LogicalSolver;
+    use super::write_string;
+
+    const EASY: &str = "530070000600195000098000060800060003400803001700020006060000280000419005000080079";
+
+    #[test]
+    fn trace_records_every_step() {
+        let board = Board::from_singleline_str(EASY).unwrap();
+        let trace = LogicalSolver::new().trace(&board).unwrap();
+        assert!(trace.solved);
+        assert_eq!(board, trace.puzzle);
+        assert_eq!(81 - 30, trace.steps.len());
+        assert_eq!(
+            "r3c7 is the only place left for 5 in row 3. Place 5 in r3c7.",
+            trace.steps[0].explanation);
+    }
+
+    #[test]
+    fn trace_rejects_contradictory_givens() {
+        let board = Board::from_singleline_str("110000000000000000000000000000000000000000000000000000000000000000000000000000000").unwrap();
+        let conflicts = vec![
+            Conflict { first: (0, 0), second: (0, 1), number: 1, constraint: Constraint::Row },
+            Conflict { first: (0, 0), second: (0, 1), number: 1, constraint: Constraint::Box }
+        ];
+        assert_eq!(Err(SolveError::ContradictoryGivens(conflicts.clone())), LogicalSolver::new().trace(&board));
+        assert_eq!(Err(SolveError::ContradictoryGivens(conflicts)), board.trace());
+    }
+
+    #[test]
+    fn narrative() {
+        let trace = LogicalSolver::new().trace(&Board::from_singleline_str(EASY).unwrap()).unwrap();
+        let narrative = trace.to_narrative();
+        let lines: Vec<&str> = narrative.lines().collect();
+        assert_eq!(trace.steps.len() + 2, lines.len());
+        assert_eq!(format!("Puzzle: {}", EASY), lines[0]);
+        assert_eq!("1. Hidden Single: r3c7 is the only place left for 5 in row 3. Place 5 in r3c7.", lines[1]);
+        assert_eq!("Solved in 51 steps.", lines[lines.len() - 1]);
+    }
+
+    #[test]
+    fn json() {
+        let trace = LogicalSolver::new().trace(&Board::from_singleline_str(EASY).unwrap()).unwrap();
+        let json = trace.to_json();
+        assert!(json.starts_with(&format!(
+            "{{\"puzzle\":\"{}\",\"solved\":true,\"steps\":[{{\"technique\":\"Hidden Single\",\"units\":[{{\"type\":\"row\",\"index\":2}}],\
+             \"cells\":[{{\"row\":2,\"column\":6}}],\"placements\":[{{\"row\":2,\"column\":6,\"number\":5}}],\"eliminations\":[],\"chain\":[],\
+             \"explanation\":\"r3c7 is the only place left for 5 in row 3. Place 5 in r3c7.\"}},", EASY)));
+        assert!(json.ends_with("}]}"));
+        assert_eq!(trace.steps.len(), json.matches("\"technique\"").count());
+    }
+
+    #[test]
+    fn json_escapes_strings() {
+        let mut json = String::new();
+        write_string(&mut json, "a \"b\" \\ c\n\u{1}");
+        assert_eq!("\"a \\\"b\\\" \\\\ c\\n\\u0001\"", json);
+    }
+}
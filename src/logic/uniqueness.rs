@@ -0,0 +1,275 @@
+use board::{unit_cells, unit_of, Constraint, Possibility};
+use candidates::{CandidateSet, Candidates};
+use super::{all_units, candidate, combinations, eliminations_seeing, Step, Technique};
+
+// Four empty cells at the corners of a rectangle spanning exactly two boxes, which all have the
+// candidates a and b. If the puzzle has a unique solution, they can't end up holding only a and b,
+// because the two numbers could then be swapped to give a second solution.
+struct Rectangle {
+    cells: [(usize, usize); 4],
+    a: usize,
+    b: usize
+}
+
+impl Rectangle {
+    // The corners whose only candidates are a and b.
+    fn floor(&self, candidates: &Candidates) -> Vec<(usize, usize)> {
+        let pair = self.pair();
+        self.cells.iter().cloned().filter(|&(row, column)| candidates.get_candidates(row, column) == pair).collect()
+    }
+
+    // The other corners, which have some extra candidates.
+    fn roof(&self, candidates: &Candidates) -> Vec<(usize, usize)> {
+        let pair = self.pair();
+        self.cells.iter().cloned().filter(|&(row, column)| candidates.get_candidates(row, column) != pair).collect()
+    }
+
+    fn pair(&self) -> CandidateSet {
+        let mut pair = CandidateSet::empty();
+        pair.insert(self.a);
+        pair.insert(self.b);
+        pair
+    }
+
+    fn step(&self, technique: Technique, eliminations: Vec<Possibility>) -> Option<Step> {
+        if eliminations.is_empty() {
+            return None;
+        }
+        Some(Step {
+            technique,
+            units: vec![],
+            cells: self.cells.to_vec(),
+            placements: vec![],
+            eliminations,
+            chain: vec![]
+        })
+    }
+}
+
+fn rectangles(candidates: &Candidates) -> Vec<Rectangle> {
+    let mut rectangles = Vec::new();
+    for rows in combinations(&[0, 1, 2, 3, 4, 5, 6, 7, 8], 2) {
+        for columns in combinations(&[0, 1, 2, 3, 4, 5, 6, 7, 8], 2) {
+            // Two boxes means the rows share a band or the columns share a stack, but not both.
+            if (rows[0] / 3 == rows[1] / 3) == (columns[0] / 3 == columns[1] / 3) {
+                continue;
+            }
+            let cells = [(rows[0], columns[0]), (rows[0], columns[1]), (rows[1], columns[0]), (rows[1], columns[1])];
+            let common = cells.iter().fold(CandidateSet::all(), |common, &(row, column)| {
+                common.intersection(candidates.get_candidates(row, column))
+            });
+            for pair in combinations(&common.iter().collect::<Vec<_>>(), 2) {
+                rectangles.push(Rectangle { cells, a: pair[0], b: pair[1] });
+            }
+        }
+    }
+    rectangles
+}
+
+// The rows, columns, and boxes containing both cells.
+fn shared_units(first: (usize, usize), second: (usize, usize)) -> Vec<(Constraint, usize)> {
+    [Constraint::Row, Constraint::Column, Constraint::Box].iter()
+        .filter(|&&constraint| unit_of(constraint, first.0, first.1) == unit_of(constraint, second.0, second.1))
+        .map(|&constraint| (constraint, unit_of(constraint, first.0, first.1)))
+        .collect()
+}
+
+// Three corners have only a and b, so the fourth must be something else.
+pub fn find_type_1(candidates: &Candidates) -> Option<Step> {
+    for rectangle in rectangles(candidates) {
+        let roof = rectangle.roof(candidates);
+        if roof.len() == 1 {
+            let eliminations = vec![candidate(roof[0], rectangle.a), candidate(roof[0], rectangle.b)];
+            return rectangle.step(Technique::UniqueRectangleType1, eliminations);
+        }
+    }
+    None
+}
+
+// Two corners on a line have only a and b, and the other two have just one extra candidate c.
+// One of those two must be c, so c can be eliminated from every cell seeing both.
+pub fn find_type_2(candidates: &Candidates) -> Option<Step> {
+    for rectangle in rectangles(candidates) {
+        let roof = match side_roof(&rectangle, candidates) {
+            Some(roof) => roof,
+            None => continue
+        };
+        let first_extra = candidates.get_candidates(roof[0].0, roof[0].1).difference(rectangle.pair());
+        let second_extra = candidates.get_candidates(roof[1].0, roof[1].1).difference(rectangle.pair());
+        if let Some(c) = first_extra.single() {
+            if first_extra == second_extra {
+                let step = rectangle.step(Technique::UniqueRectangleType2, eliminations_seeing(candidates, c, &roof));
+                if step.is_some() {
+                    return step;
+                }
+            }
+        }
+    }
+    None
+}
+
+// Two corners on a line have only a and b, so one of the other two must hold one of their extra
+// candidates. Those two cells act like a single cell with just the extra candidates, which can form
+// a naked subset with other cells of a unit they share.
+pub fn find_type_3(candidates: &Candidates) -> Option<Step> {
+    for rectangle in rectangles(candidates) {
+        let roof = match side_roof(&rectangle, candidates) {
+            Some(roof) => roof,
+            None => continue
+        };
+        let extras = candidates.get_candidates(roof[0].0, roof[0].1)
+            .union(candidates.get_candidates(roof[1].0, roof[1].1))
+            .difference(rectangle.pair());
+        for (constraint, index) in shared_units(roof[0], roof[1]) {
+            let cells = unit_cells(constraint, index);
+            let others: Vec<(usize, usize)> = cells.iter()
+                .cloned()
+                .filter(|cell| !roof.contains(cell) && candidates.get_candidates(cell.0, cell.1).len() >= 2)
+                .collect();
+            for size in 1..4 {
+                for subset in combinations(&others, size) {
+                    let numbers = subset.iter().fold(extras, |numbers, &(row, column)| numbers.union(candidates.get_candidates(row, column)));
+                    if numbers.len() != size + 1 {
+                        continue;
+                    }
+                    let eliminations: Vec<Possibility> = cells.iter()
+                        .filter(|cell| !roof.contains(cell) && !subset.contains(cell))
+                        .flat_map(|&(row, column)| {
+                            candidates.get_candidates(row, column).intersection(numbers).iter()
+                                .map(move |number| Possibility::new(row, column, number))
+                        })
+                        .collect();
+                    let step = rectangle.step(Technique::UniqueRectangleType3, eliminations);
+                    if step.is_some() {
+                        return step;
+                    }
+                }
+            }
+        }
+    }
+    None
+}
+
+// Two corners on a line have only a and b, and the other two are the only places for a in some
+// unit they share. One of them must be a, so neither can be b.
+pub fn find_type_4(candidates: &Candidates) -> Option<Step> {
+    for rectangle in rectangles(candidates) {
+        let roof = match side_roof(&rectangle, candidates) {
+            Some(roof) => roof,
+            None => continue
+        };
+        for (constraint, index) in shared_units(roof[0], roof[1]) {
+            for &(locked, other) in &[(rectangle.a, rectangle.b), (rectangle.b, rectangle.a)] {
+                let places = unit_cells(constraint, index).iter()
+                    .filter(|&&(row, column)| candidates.is_candidate(row, column, locked))
+                    .count();
+                if places == 2 {
+                    let eliminations = vec![candidate(roof[0], other), candidate(roof[1], other)];
+                    return rectangle.step(Technique::UniqueRectangleType4, eliminations);
+                }
+            }
+        }
+    }
+    None
+}
+
+// The two corners with extra candidates, if they are on the same row or column.
+fn side_roof(rectangle: &Rectangle, candidates: &Candidates) -> Option<Vec<(usize, usize)>> {
+    let roof = rectangle.roof(candidates);
+    if rectangle.floor(candidates).len() == 2 && (roof[0].0 == roof[1].0 || roof[0].1 == roof[1].1) {
+        Some(roof)
+    } else {
+        None
+    }
+}
+
+// Bivalue Universal Grave plus one: every empty cell has two candidates except one with three, and
+// every number's places in every unit come in pairs apart from one number in that cell. Without that
+// number the grid would be a deadly pattern with two solutions, so it must be placed.
+pub fn find_bug_plus_one(candidates: &Candidates) -> Option<Step> {
+    let mut extra_cell = None;
+    for row in 0..9 {
+        for column in 0..9 {
+            match candidates.get_candidates(row, column).len() {
+                0 | 2 => (),
+                3 if extra_cell.is_none() => extra_cell = Some((row, column)),
+                _ => return None
+            }
+        }
+    }
+    let (row, column) = extra_cell?;
+
+    let mut extra_number = None;
+    for (constraint, index) in all_units() {
+        for number in 1..10 {
+            let places: Vec<(usize, usize)> = unit_cells(constraint, index).iter()
+                .cloned()
+                .filter(|&(row, column)| candidates.is_candidate(row, column, number))
+                .collect();
+            match places.len() {
+                0 | 2 => (),
+                3 if places.contains(&(row, column)) && extra_number.is_none_or(|extra| extra == number) => extra_number = Some(number),
+                _ => return None
+            }
+        }
+    }
+    let number = extra_number?;
+
+    Some(Step {
+        technique: Technique::BugPlusOne,
+        units: vec![],
+        cells: vec![(row, column)],
+        placements: vec![Possibility::new(row, column, number)],
+        eliminations: vec![],
+        chain: vec![]
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use board::Board;
+    use candidates::Candidates;
+    use logic::Technique;
+    use logic::tests::find_and_check;
+
+    #[test]
+    fn type_1() {
+        let step = find_and_check("007092081190050020000700500600400030000009002000800700200000000004007008800000016", Technique::UniqueRectangleType1);
+        assert_eq!(4, step.cells.len());
+        assert_eq!(2, step.eliminations.len());
+    }
+
+    #[test]
+    fn type_2() {
+        let step = find_and_check("790030000006000000300000090020008000040600750050902040030000620009200013000000405", Technique::UniqueRectangleType2);
+        assert!(step.eliminations.iter().all(|elimination| elimination.number() == step.eliminations[0].number()));
+    }
+
+    #[test]
+    fn type_3() {
+        find_and_check("000400002150300080000002009900040000700001800000050090000000200007680000609070010", Technique::UniqueRectangleType3);
+    }
+
+    #[test]
+    fn type_4() {
+        let step = find_and_check("003580700000070064100000009016790000200006000040030900800010400000000000400052000", Technique::UniqueRectangleType4);
+        assert_eq!(2, step.eliminations.len());
+    }
+
+    #[test]
+    fn bug_plus_one() {
+        let step = find_and_check("060000809920000000008700000000001960700460500000800042109008020400000030000143000", Technique::BugPlusOne);
+        assert_eq!(1, step.placements.len());
+    }
+
+    #[test]
+    fn no_rectangle_in_deadly_pattern() {
+        // The four cells could each be 1 or 2 in two different solutions, so there's nothing to
+        // deduce from them.
+        let board = Board::from_singleline_str("534678912672195300198342567859761423426853791713924856961537200287419635345286179").unwrap();
+        let candidates = Candidates::from_board(&board);
+        for &technique in &[Technique::UniqueRectangleType1, Technique::UniqueRectangleType2, Technique::UniqueRectangleType3, Technique::UniqueRectangleType4] {
+            assert_eq!(None, technique.find(&candidates));
+        }
+    }
+}
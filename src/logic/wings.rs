@@ -0,0 +1,168 @@
+use candidates::Candidates;
+use super::{alternating_links, bivalue_cells, candidate, conjugate_pairs, eliminations_seeing, sees, Link, LinkKind, Step, Technique};
+
+// A pivot cell with candidates xy sees two pincer cells with candidates xz and yz. Whichever number
+// the pivot takes, one of the pincers must be z, so z can be eliminated from every cell seeing both.
+pub fn find_xy_wing(candidates: &Candidates) -> Option<Step> {
+    let bivalue = bivalue_cells(candidates);
+    for &(pivot, pivot_set) in &bivalue {
+        for &(first, first_set) in &bivalue {
+            let x = match pivot_set.intersection(first_set).single() {
+                Some(x) if sees(pivot, first) => x,
+                _ => continue
+            };
+            let y = pivot_set.difference(first_set).single().unwrap();
+            let z = first_set.difference(pivot_set).single().unwrap();
+            for &(second, second_set) in &bivalue {
+                if !sees(pivot, second) || !second_set.contains(y) || !second_set.contains(z) {
+                    continue;
+                }
+                let eliminations = eliminations_seeing(candidates, z, &[first, second]);
+                if !eliminations.is_empty() {
+                    return Some(Step {
+                        technique: Technique::XYWing,
+                        units: vec![],
+                        cells: vec![pivot, first, second],
+                        placements: vec![],
+                        eliminations,
+                        chain: alternating_links(&[
+                            candidate(first, z), candidate(first, x),
+                            candidate(pivot, x), candidate(pivot, y),
+                            candidate(second, y), candidate(second, z)
+                        ])
+                    });
+                }
+            }
+        }
+    }
+    None
+}
+
+// A pivot cell with candidates xyz sees two pincer cells with candidates xz and yz. One of the three
+// cells must be z, so z can be eliminated from every cell seeing all of them. There is no single
+// chain behind the deduction, so the step's chain holds the two branches from the pivot instead: if
+// the pivot is x the first pincer is z, and if it is y the second pincer is z.
+pub fn find_xyz_wing(candidates: &Candidates) -> Option<Step> {
+    let bivalue = bivalue_cells(candidates);
+    for row in 0..9 {
+        for column in 0..9 {
+            let pivot = (row, column);
+            let pivot_set = candidates.get_candidates(row, column);
+            if pivot_set.len() != 3 {
+                continue;
+            }
+            let pincers: Vec<_> = bivalue.iter()
+                .filter(|&&(cell, set)| sees(pivot, cell) && set.difference(pivot_set).is_empty())
+                .collect();
+            for (i, &&(first, first_set)) in pincers.iter().enumerate() {
+                for &&(second, second_set) in &pincers[i + 1..] {
+                    let z = match first_set.intersection(second_set).single() {
+                        Some(z) => z,
+                        None => continue
+                    };
+                    let eliminations = eliminations_seeing(candidates, z, &[pivot, first, second]);
+                    if !eliminations.is_empty() {
+                        let mut chain = Vec::new();
+                        for &(pincer, pincer_set) in &[(first, first_set), (second, second_set)] {
+                            let number = pincer_set.iter().find(|&number| number != z).unwrap();
+                            chain.push(Link { from: candidate(pivot, number), to: candidate(pincer, number), kind: LinkKind::Weak });
+                            chain.push(Link { from: candidate(pincer, number), to: candidate(pincer, z), kind: LinkKind::Strong });
+                        }
+                        return Some(Step {
+                            technique: Technique::XYZWing,
+                            units: vec![],
+                            cells: vec![pivot, first, second],
+                            placements: vec![],
+                            eliminations,
+                            chain
+                        });
+                    }
+                }
+            }
+        }
+    }
+    None
+}
+
+// Two cells with the same candidates xy don't see each other, but are joined by a strong link on x:
+// a unit where x can only go in a cell seeing one of them or a cell seeing the other. If neither
+// were y, both would be x, leaving nowhere for x in that unit. So y can be eliminated from every
+// cell seeing both.
+pub fn find_w_wing(candidates: &Candidates) -> Option<Step> {
+    let bivalue = bivalue_cells(candidates);
+    for (i, &(first, set)) in bivalue.iter().enumerate() {
+        for &(second, _) in bivalue[i + 1..].iter().filter(|&&(cell, other_set)| other_set == set && !sees(first, cell)) {
+            for x in set.iter() {
+                let y = set.iter().find(|&number| number != x).unwrap();
+                for (a, b) in conjugate_pairs(candidates, x) {
+                    let (near, far) = if sees(first, a) && sees(second, b) {
+                        (a, b)
+                    } else if sees(first, b) && sees(second, a) {
+                        (b, a)
+                    } else {
+                        continue
+                    };
+                    let eliminations = eliminations_seeing(candidates, y, &[first, second]);
+                    if !eliminations.is_empty() {
+                        return Some(Step {
+                            technique: Technique::WWing,
+                            units: vec![],
+                            cells: vec![first, near, far, second],
+                            placements: vec![],
+                            eliminations,
+                            chain: alternating_links(&[
+                                candidate(first, y), candidate(first, x),
+                                candidate(near, x), candidate(far, x),
+                                candidate(second, x), candidate(second, y)
+                            ])
+                        });
+                    }
+                }
+            }
+        }
+    }
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use logic::{LinkKind, Technique};
+    use logic::tests::find_and_check;
+
+    const WINGS: &str = "000400002150300080000002009900040000700001800000050090000000200007680000609070010";
+
+    #[test]
+    fn xy_wing() {
+        let step = find_and_check(WINGS, Technique::XYWing);
+        assert_eq!(3, step.cells.len());
+        assert_eq!(5, step.chain.len());
+        assert_eq!(LinkKind::Strong, step.chain[0].kind);
+        assert_eq!(step.chain[0].from.number(), step.eliminations[0].number());
+        assert_eq!(step.chain[4].to.number(), step.eliminations[0].number());
+    }
+
+    #[test]
+    fn xyz_wing() {
+        let step = find_and_check("600500000300080050000200010004009000005016030000000004080000005010090008070800600", Technique::XYZWing);
+        assert_eq!(3, step.cells.len());
+        let z = step.eliminations[0].number();
+        assert_eq!(4, step.chain.len());
+        for (branch, pincer) in step.chain.chunks(2).zip(&step.cells[1..]) {
+            assert_eq!(LinkKind::Weak, branch[0].kind);
+            assert_eq!(step.cells[0], (branch[0].from.row(), branch[0].from.column()));
+            assert_eq!(*pincer, (branch[0].to.row(), branch[0].to.column()));
+            assert_eq!(LinkKind::Strong, branch[1].kind);
+            assert_eq!(branch[0].to, branch[1].from);
+            assert_eq!(z, branch[1].to.number());
+        }
+    }
+
+    #[test]
+    fn w_wing() {
+        let step = find_and_check(WINGS, Technique::WWing);
+        assert_eq!(5, step.chain.len());
+        for pair in step.chain.windows(2) {
+            assert_eq!(pair[0].to, pair[1].from);
+        }
+    }
+}
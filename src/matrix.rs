@@ -1,33 +1,16 @@
 use std::fmt::{Display, Debug, Formatter};
 
-// 729 possibilities, aka rows in the exact cover matrix. The number comes from
-// 9 * 9 cells on the board, each of which can have one of 9 numbers.
-const POSSIBILITIES: usize = 9 * 9 * 9;
-// 324 constraints, aka columns in the exact cover matrix. There are 9 cell
-// constraints, 9 row constraints, 9 column constraints, and 9 box constraints,
-// each of which consist of 9 numbers.
-const CONSTRAINTS: usize = 9 * 9 + 9 * 9 + 9 * 9 + 9 * 9;
-// Each possibility contributes only 4 ones in the exact cover matrix. This fact,
-// combined with the regular nature of the sudoku exact cover matrix, allows us to
-// represent the sparse exact cover matrix in a space-efficient dense representation.
-const NONZERO_CONSTRAINTS_PER_POSSIBILITY: usize = 4;
-// Each of the POSSIBILITIES rows has four nonzero column entries, plus one header node for
-// every one of the CONSTRAINTS columns, plus one root.
-const MATRIX_NODE_COUNT: usize = 1 + CONSTRAINTS + POSSIBILITIES * NONZERO_CONSTRAINTS_PER_POSSIBILITY;
-
-#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+use rng::Rng;
+use SolveError;
+
+#[derive(Debug, Default, Clone, Copy, Eq, PartialEq)]
 enum NodeKind {
+    #[default]
     Root,
     Header,
     Entry
 }
 
-impl Default for NodeKind {
-    fn default() -> Self {
-        NodeKind::Root
-    }
-}
-
 #[derive(Debug, Default, Clone, Copy, Eq, PartialEq)]
 struct Node {
     kind: NodeKind,
@@ -60,8 +43,8 @@ impl Matrix {
     const ROOT_INDEX: usize = 0;
 
     pub fn new(row_count: usize, column_count: usize, entry_capacity: usize) -> Self {
-        assert!(row_count > 0 && column_count < ::std::usize::MAX);
-        assert!(column_count > 0 && row_count < ::std::usize::MAX);
+        assert!(row_count > 0 && column_count < usize::MAX);
+        assert!(column_count > 0 && row_count < usize::MAX);
 
         let nodes = Vec::with_capacity(1 + column_count + entry_capacity);
         let row_fronts = vec![None; row_count];
@@ -78,9 +61,9 @@ impl Matrix {
         // Create root.
         self.nodes.push(Node {
             kind: NodeKind::Root,
-            column_size: ::std::usize::MAX,
-            column_index: ::std::usize::MAX,
-            row_index: ::std::usize::MAX,
+            column_size: usize::MAX,
+            column_index: usize::MAX,
+            row_index: usize::MAX,
             up: Matrix::ROOT_INDEX,
             down: Matrix::ROOT_INDEX,
             left: self.column_count,
@@ -94,7 +77,7 @@ impl Matrix {
                 kind: NodeKind::Header,
                 column_size: 0,
                 column_index: column,
-                row_index: ::std::usize::MAX,
+                row_index: usize::MAX,
                 up: column_index,
                 down: column_index,
                 // Note this is correct even when column == 0, because the left neighbor of the
@@ -160,7 +143,7 @@ impl Matrix {
         // Finish by actually adding the new entry snode.
         self.nodes.push(Node {
             kind: NodeKind::Entry,
-            column_size: ::std::usize::MAX,
+            column_size: usize::MAX,
             column_index,
             row_index,
             up: header_index,
@@ -182,13 +165,13 @@ impl Matrix {
 
         // Go down to every node in this column. Stop once we reach the header node again.
         let mut current_down_index = self.nodes[header_index].down;
-        while (current_down_index != header_index) {
+        while current_down_index != header_index {
             // Go right to every node in this row. For each node in the row EXCEPT (!) the one in
             // this covered column itself, unlink it from its respective column by making its up and
             // down nodes point to each other. Also remember to decrement the column size for the columns
             // that have nodes unlinked.
             let mut current_right_index = self.nodes[current_down_index].right;
-            while (current_right_index != current_down_index) {
+            while current_right_index != current_down_index {
                 let current_right_header_index = self.nodes[current_right_index].column_index + 1;
                 assert!(current_right_header_index != header_index,
                     "When traversing right in cover_column, tried to unlink a node from the same column that is being covered");
@@ -220,13 +203,13 @@ impl Matrix {
 
         // Go up to every node in this column. Stop once we reach the header node again.
         let mut current_up_index = self.nodes[header_index].up;
-        while (current_up_index != header_index) {
+        while current_up_index != header_index {
             // Go left to every node in this row. For each node in the row EXCEPT (!) the one in
             // this covered column itself, restore it to its respective column by making its up and
             // down nodes point to the node. Also remember to increment the column size for the columns
             // that have nodes restored.
             let mut current_left_index = self.nodes[current_up_index].left;
-            while (current_left_index != current_up_index) {
+            while current_left_index != current_up_index {
                 let current_left_header_index = self.nodes[current_left_index].column_index + 1;
                 assert!(current_left_header_index != header_index,
                     "When traversing left in uncover_column, tried to restore a node from the same column that is being uncovered");
@@ -254,49 +237,86 @@ impl Matrix {
         self.nodes[left_neighbor_index].right = header_index;
     }
 
-    // This should probably return a Vec<Vec<usize>> (or better yet an iterator over solutions)
-    // because there can be multiple solutions for a given puzzle, however for now we just return
-    // the first one found.
-    pub fn solve(&mut self) -> Result<Vec<usize>, ()> {
+    // Returns the first solution found. Use `solve_all` or `for_each_solution` to visit every
+    // solution when the matrix may have more than one exact cover. The matrix is restored to its
+    // pre-search state afterwards, so it can be searched again without being rebuilt.
+    pub fn solve(&mut self) -> Result<Vec<usize>, SolveError> {
         let mut solution_rows = Vec::with_capacity(self.row_count);
         if self.search_first(&mut solution_rows) {
             Ok(solution_rows)
         } else {
-            Err(())
+            Err(SolveError::NoSolution)
+        }
+    }
+
+    // Like `solve`, but tries the rows of each column in a random order, so that a matrix with
+    // many exact covers gives a random one of them. The same generator state always gives the
+    // same cover. The matrix is restored to its pre-search state afterwards.
+    pub(crate) fn solve_random(&mut self, rng: &mut Rng) -> Result<Vec<usize>, SolveError> {
+        let mut solution_rows = Vec::with_capacity(self.row_count);
+        if self.search_random(&mut solution_rows, rng) {
+            Ok(solution_rows)
+        } else {
+            Err(SolveError::NoSolution)
+        }
+    }
+
+    // Returns every exact cover of the matrix, each as the list of row indices making up that
+    // cover. The matrix is restored to its pre-search state afterwards.
+    pub fn solve_all(&mut self) -> Vec<Vec<usize>> {
+        let mut solutions = Vec::new();
+        self.for_each_solution(|solution_rows| solutions.push(solution_rows.to_vec()));
+        solutions
+    }
+
+    // Invokes `callback` once for every exact cover of the matrix, passing the row indices of
+    // the rows in that cover. The matrix is restored to its pre-search state afterwards.
+    pub fn for_each_solution<F: FnMut(&[usize])>(&mut self, mut callback: F) {
+        let mut solution_rows = Vec::with_capacity(self.row_count);
+        self.search_all(&mut solution_rows, &mut callback);
+    }
+
+    // Counts exact covers of the matrix, stopping as soon as `limit` have been found. A limit of 2
+    // is enough to tell whether a matrix has no, exactly one, or more than one solution. The matrix
+    // is restored to its pre-search state afterwards.
+    pub fn count_solutions(&mut self, limit: usize) -> usize {
+        let mut count = 0;
+        if limit > 0 {
+            self.search_count(limit, &mut count, &mut Vec::new(), &mut Vec::new());
+        }
+        count
+    }
+
+    // Returns the only exact cover of the matrix, failing if there is none or more than one. A
+    // single search settles both, as it stops at the second cover and keeps the first. The matrix
+    // is restored to its pre-search state afterwards.
+    pub fn solve_unique(&mut self) -> Result<Vec<usize>, SolveError> {
+        let mut count = 0;
+        let mut first = Vec::new();
+        self.search_count(2, &mut count, &mut Vec::with_capacity(self.row_count), &mut first);
+        match count {
+            0 => Err(SolveError::NoSolution),
+            1 => Ok(first),
+            _ => Err(SolveError::MultipleSolutions)
         }
     }
 
     // https://arxiv.org/pdf/cs/0011047.pdf
     // Returns true if a solution was found, false otherwise. If a solution was found then
     // the solution_rows will contain the row indices of all rows in the solution, otherwise
-    // the solution_rows will have the same contents it had when the function was called.
+    // the solution_rows will have the same contents it had when the function was called. Either
+    // way the matrix is restored to the state it was in when the function was called.
     fn search_first(&mut self, solution_rows: &mut Vec<usize>) -> bool {
         // If all columns are covered, then we've found a solution.
         if self.nodes[Matrix::ROOT_INDEX].right == Matrix::ROOT_INDEX {
             return true;
         }
 
-        // Choose the column with the fewest nodes remaining in it.
-        let (min_header_index, min_column_size) = {
-            let mut min_column_size = ::std::usize::MAX;
-            let mut min_header_index = Matrix::ROOT_INDEX;
-            let mut current_index = self.nodes[Matrix::ROOT_INDEX].right;
-            while current_index != Matrix::ROOT_INDEX {
-                let column_size = self.nodes[current_index].column_size;
-                if column_size < min_column_size {
-                    min_column_size = column_size;
-                    min_header_index = current_index;
-                }
-                current_index = self.nodes[current_index].right;
-            }
-            assert!(min_header_index != Matrix::ROOT_INDEX);
-            (min_header_index, min_column_size)
-        };
-
         // If we found a column with no nodes in it, then there is no exact cover solution.
-        if min_column_size == 0 {
-            return false;
-        }
+        let min_header_index = match self.choose_column() {
+            Some(min_header_index) => min_header_index,
+            None => return false
+        };
 
         // Cover the current column.
         let min_column_index = self.nodes[min_header_index].column_index;
@@ -304,37 +324,24 @@ impl Matrix {
 
         // Go through every row in the minimum-sized column and try adding it to the solution.
         let mut current_down_index = self.nodes[min_header_index].down;
-        while (current_down_index != min_header_index) {
+        while current_down_index != min_header_index {
             // Add the current row to the solution.
             solution_rows.push(self.nodes[current_down_index].row_index);
-
-            // Traverse right across the row, covering all columns with an entry in this row.
-            let mut current_right_index = self.nodes[current_down_index].right;
-            while (current_right_index != current_down_index) {
-                let column_index_to_cover = self.nodes[current_right_index].column_index;
-                self.cover_column(column_index_to_cover);
-                current_right_index = self.nodes[current_right_index].right;
-            }
+            self.cover_row(current_down_index);
 
             // Recursively search the reduced matrix.
-            if self.search_first(solution_rows) {
-                // For now we're only interested in one solution, so we unwind the call stack
+            let found = self.search_first(solution_rows);
+            self.uncover_row(current_down_index);
+            if found {
+                // We're only interested in one solution, so we unwind the call stack
                 // (importantly, without popping any solution rows) as soon as the first solution
-                // is found. Future improvements would be to implement an efficient iterator over
-                // solutions.
+                // is found, restoring the matrix on the way out.
+                self.uncover_column(min_column_index);
                 return true;
             }
 
             solution_rows.pop();
 
-            // Traverse left across the row, restoring all columns with an entry in this row.
-            let mut current_left_index = self.nodes[current_down_index].left;
-            while (current_left_index != current_down_index) {
-                let column_index_to_cover = self.nodes[current_left_index].column_index;
-                self.uncover_column(column_index_to_cover);
-                current_left_index = self.nodes[current_left_index].left;
-            }
-
             // Continue down the column.
             current_down_index = self.nodes[current_down_index].down;
         }
@@ -344,6 +351,164 @@ impl Matrix {
 
         false
     }
+
+    // The same search as `search_first`, except that the rows of the chosen column are shuffled
+    // before being tried.
+    fn search_random(&mut self, solution_rows: &mut Vec<usize>, rng: &mut Rng) -> bool {
+        if self.nodes[Matrix::ROOT_INDEX].right == Matrix::ROOT_INDEX {
+            return true;
+        }
+
+        let min_header_index = match self.choose_column() {
+            Some(min_header_index) => min_header_index,
+            None => return false
+        };
+        let min_column_index = self.nodes[min_header_index].column_index;
+
+        // Collect the column's rows before covering it, then try them in a random order.
+        let mut row_nodes = Vec::with_capacity(self.nodes[min_header_index].column_size);
+        let mut current_down_index = self.nodes[min_header_index].down;
+        while current_down_index != min_header_index {
+            row_nodes.push(current_down_index);
+            current_down_index = self.nodes[current_down_index].down;
+        }
+        rng.shuffle(&mut row_nodes);
+
+        self.cover_column(min_column_index);
+        for row_node in row_nodes {
+            solution_rows.push(self.nodes[row_node].row_index);
+            self.cover_row(row_node);
+            let found = self.search_random(solution_rows, rng);
+            self.uncover_row(row_node);
+            if found {
+                self.uncover_column(min_column_index);
+                return true;
+            }
+            solution_rows.pop();
+        }
+        self.uncover_column(min_column_index);
+
+        false
+    }
+
+    // https://arxiv.org/pdf/cs/0011047.pdf
+    // Unlike search_first, this visits every solution and always leaves both the matrix and
+    // the solution_rows in the state they were in when the function was called.
+    fn search_all<F: FnMut(&[usize])>(&mut self, solution_rows: &mut Vec<usize>, callback: &mut F) {
+        // If all columns are covered, then we've found a solution.
+        if self.nodes[Matrix::ROOT_INDEX].right == Matrix::ROOT_INDEX {
+            callback(solution_rows);
+            return;
+        }
+
+        // If we found a column with no nodes in it, then there is no exact cover solution
+        // down this branch.
+        let min_header_index = match self.choose_column() {
+            Some(min_header_index) => min_header_index,
+            None => return
+        };
+
+        let min_column_index = self.nodes[min_header_index].column_index;
+        self.cover_column(min_column_index);
+
+        let mut current_down_index = self.nodes[min_header_index].down;
+        while current_down_index != min_header_index {
+            solution_rows.push(self.nodes[current_down_index].row_index);
+            self.cover_row(current_down_index);
+
+            self.search_all(solution_rows, callback);
+
+            solution_rows.pop();
+            self.uncover_row(current_down_index);
+
+            current_down_index = self.nodes[current_down_index].down;
+        }
+
+        self.uncover_column(min_column_index);
+    }
+
+    // https://arxiv.org/pdf/cs/0011047.pdf
+    // Like search_all, but only counts solutions and unwinds as soon as `count` reaches `limit`.
+    // The rows of the first solution found are copied into `first`. The matrix is always
+    // restored, even when the search stops early.
+    fn search_count(&mut self, limit: usize, count: &mut usize, solution_rows: &mut Vec<usize>, first: &mut Vec<usize>) {
+        // If all columns are covered, then we've found a solution.
+        if self.nodes[Matrix::ROOT_INDEX].right == Matrix::ROOT_INDEX {
+            if *count == 0 {
+                first.extend_from_slice(solution_rows);
+            }
+            *count += 1;
+            return;
+        }
+
+        let min_header_index = match self.choose_column() {
+            Some(min_header_index) => min_header_index,
+            None => return
+        };
+
+        let min_column_index = self.nodes[min_header_index].column_index;
+        self.cover_column(min_column_index);
+
+        let mut current_down_index = self.nodes[min_header_index].down;
+        while current_down_index != min_header_index {
+            solution_rows.push(self.nodes[current_down_index].row_index);
+            self.cover_row(current_down_index);
+            self.search_count(limit, count, solution_rows, first);
+            solution_rows.pop();
+            self.uncover_row(current_down_index);
+
+            // Stop exploring the remaining rows once enough solutions have been seen.
+            if *count >= limit {
+                break;
+            }
+
+            current_down_index = self.nodes[current_down_index].down;
+        }
+
+        self.uncover_column(min_column_index);
+    }
+
+    // Chooses the column with the fewest nodes remaining in it, returning the `nodes` index
+    // of its header. Returns None if that column is empty, because then there can be no
+    // exact cover. Must only be called while at least one column remains uncovered.
+    fn choose_column(&self) -> Option<usize> {
+        let mut min_column_size = usize::MAX;
+        let mut min_header_index = Matrix::ROOT_INDEX;
+        let mut current_index = self.nodes[Matrix::ROOT_INDEX].right;
+        while current_index != Matrix::ROOT_INDEX {
+            let column_size = self.nodes[current_index].column_size;
+            if column_size < min_column_size {
+                min_column_size = column_size;
+                min_header_index = current_index;
+            }
+            current_index = self.nodes[current_index].right;
+        }
+        assert!(min_header_index != Matrix::ROOT_INDEX);
+
+        if min_column_size == 0 { None } else { Some(min_header_index) }
+    }
+
+    // Traverse right across the row containing `node_index`, covering all columns with an
+    // entry in this row other than the node's own column.
+    fn cover_row(&mut self, node_index: usize) {
+        let mut current_right_index = self.nodes[node_index].right;
+        while current_right_index != node_index {
+            let column_index_to_cover = self.nodes[current_right_index].column_index;
+            self.cover_column(column_index_to_cover);
+            current_right_index = self.nodes[current_right_index].right;
+        }
+    }
+
+    // Traverse left across the row containing `node_index`, restoring all columns with an
+    // entry in this row other than the node's own column. Undoes `cover_row`.
+    fn uncover_row(&mut self, node_index: usize) {
+        let mut current_left_index = self.nodes[node_index].left;
+        while current_left_index != node_index {
+            let column_index_to_uncover = self.nodes[current_left_index].column_index;
+            self.uncover_column(column_index_to_uncover);
+            current_left_index = self.nodes[current_left_index].left;
+        }
+    }
 }
 
 impl Debug for Matrix {
@@ -355,8 +520,8 @@ impl Debug for Matrix {
 impl Display for Matrix {
     fn fmt(&self, f: &mut Formatter) -> Result<(), ::std::fmt::Error> {
         let mut current_header_index = self.nodes[Matrix::ROOT_INDEX].right;
-        while (current_header_index != Matrix::ROOT_INDEX) {
-            writeln!(f, "col {:?}", self.nodes[current_header_index]);
+        while current_header_index != Matrix::ROOT_INDEX {
+            writeln!(f, "col {:?}", self.nodes[current_header_index])?;
             current_header_index = self.nodes[current_header_index].right;
         }
         for row in 0..self.row_count {
@@ -373,7 +538,7 @@ impl Display for Matrix {
                         node.up,
                         node.down)?;
                     current_index = node.right;
-                    if (current_index == row_front_index) {
+                    if current_index == row_front_index {
                         break;
                     }
                 }
@@ -386,7 +551,9 @@ impl Display for Matrix {
 
 #[cfg(test)]
 mod tests {
-    use super::{Matrix, Node, NodeKind};
+    use rng::Rng;
+    use SolveError;
+    use super::Matrix;
 
     #[test]
     fn new_matrix() {
@@ -403,4 +570,112 @@ mod tests {
         // assert_eq!(root, root_up);
         // assert_eq!(root, root_down);
     }
+
+    #[test]
+    fn solve_all_finds_every_cover() {
+        // Columns 0 and 1 can be covered by rows 0 and 1, by rows 0 and 4, by row 2 alone, or
+        // by row 3 alone. Rows 1 and 4 both cover only column 1, so each pairs with row 0.
+        let mut matrix = Matrix::new(5, 2, 7);
+        matrix.set_entry(0, 0);
+        matrix.set_entry(1, 1);
+        matrix.set_entry(2, 0);
+        matrix.set_entry(2, 1);
+        matrix.set_entry(3, 0);
+        matrix.set_entry(3, 1);
+        matrix.set_entry(4, 1);
+        let pristine = matrix.clone();
+
+        let mut solutions: Vec<Vec<usize>> = matrix.solve_all().into_iter()
+            .map(|mut rows| { rows.sort(); rows })
+            .collect();
+        solutions.sort();
+        assert_eq!(vec![vec![0, 1], vec![0, 4], vec![2], vec![3]], solutions);
+
+        // Enumeration must leave the matrix exactly as it found it.
+        assert!(pristine == matrix);
+    }
+
+    #[test]
+    fn solve_restores_matrix() {
+        let mut matrix = Matrix::new(3, 2, 4);
+        matrix.set_entry(0, 0);
+        matrix.set_entry(1, 0);
+        matrix.set_entry(1, 1);
+        matrix.set_entry(2, 1);
+        let pristine = matrix.clone();
+
+        assert_eq!(Ok(vec![1]), matrix.solve());
+        assert!(pristine == matrix);
+        // A second search over the restored matrix finds the same solution.
+        assert_eq!(Ok(vec![1]), matrix.solve());
+    }
+
+    #[test]
+    fn solve_random_depends_on_seed() {
+        // Every row covers the single column, so each is a cover of its own.
+        let mut matrix = Matrix::new(8, 1, 8);
+        for row in 0..8 {
+            matrix.set_entry(row, 0);
+        }
+        let pristine = matrix.clone();
+
+        let covers: Vec<Vec<usize>> = (0..16).map(|seed| matrix.solve_random(&mut Rng::new(seed)).unwrap()).collect();
+        assert!(pristine == matrix);
+        assert!(covers.iter().all(|cover| cover.len() == 1));
+        assert!(covers.iter().any(|cover| cover != &covers[0]));
+        assert_eq!(covers[3], matrix.solve_random(&mut Rng::new(3)).unwrap());
+    }
+
+    #[test]
+    fn count_solutions_stops_at_limit() {
+        let mut matrix = Matrix::new(4, 2, 6);
+        matrix.set_entry(0, 0);
+        matrix.set_entry(1, 1);
+        matrix.set_entry(2, 0);
+        matrix.set_entry(2, 1);
+        matrix.set_entry(3, 0);
+        matrix.set_entry(3, 1);
+        let pristine = matrix.clone();
+
+        assert_eq!(0, matrix.count_solutions(0));
+        assert_eq!(1, matrix.count_solutions(1));
+        assert!(pristine == matrix);
+        assert_eq!(2, matrix.count_solutions(2));
+        assert!(pristine == matrix);
+        assert_eq!(3, matrix.count_solutions(10));
+        assert!(pristine == matrix);
+    }
+
+    #[test]
+    fn solve_unique_keeps_first_cover() {
+        // Row 0 leaves column 1 uncovered, so row 1 is the only cover.
+        let mut matrix = Matrix::new(2, 2, 3);
+        matrix.set_entry(0, 0);
+        matrix.set_entry(1, 0);
+        matrix.set_entry(1, 1);
+        let pristine = matrix.clone();
+        assert_eq!(Ok(vec![1]), matrix.solve_unique());
+        assert!(pristine == matrix);
+
+        // Rows 0 and 1 each cover both columns on their own.
+        let mut ambiguous = Matrix::new(2, 2, 4);
+        ambiguous.set_entry(0, 0);
+        ambiguous.set_entry(0, 1);
+        ambiguous.set_entry(1, 0);
+        ambiguous.set_entry(1, 1);
+        assert_eq!(Err(SolveError::MultipleSolutions), ambiguous.solve_unique());
+
+        let mut uncoverable = Matrix::new(2, 2, 2);
+        uncoverable.set_entry(0, 0);
+        uncoverable.set_entry(1, 0);
+        assert_eq!(Err(SolveError::NoSolution), uncoverable.solve_unique());
+    }
+
+    #[test]
+    fn solve_all_without_cover() {
+        let mut matrix = Matrix::new(2, 2, 2);
+        matrix.set_entry(0, 0);
+        matrix.set_entry(1, 0);
+        assert!(matrix.solve_all().is_empty());
+    }
 }
\ No newline at end of file
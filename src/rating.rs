@@ -0,0 +1,209 @@
+use std::fmt::{Display, Formatter};
+
+use board::Board;
+use candidates::Candidates;
+use logic::{LogicalSolver, Technique};
+use SolveError;
+
+/// How hard a puzzle is for a person to solve.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Rating {
+    /// The rating of the hardest technique needed, see `Technique::rating`. Puzzles needing only
+    /// hidden singles score 1.5, and those needing forcing chains over 8. A complete board scores 0.
+    pub score: f32,
+    /// The hardest technique needed, if any.
+    pub hardest: Option<Technique>,
+    /// The number of steps in the logical solve.
+    pub steps: usize
+}
+
+/// A band of difficulty, which generated puzzles can be required to fall within. See
+/// `Generator::generate_with_difficulty`.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Difficulty {
+    /// Puzzles whose `Rating::score` from `Rater::new` is within [min, max].
+    Rating { min: f32, max: f32 },
+    /// Puzzles which can be solved using only the allowed techniques, and whose solve needs every
+    /// required technique. The simplest allowed technique that makes progress is always used.
+    Techniques { allowed: Vec<Technique>, required: Vec<Technique> }
+}
+
+impl Difficulty {
+    /// Puzzles needing nothing harder than naked singles.
+    pub fn easy() -> Difficulty {
+        Difficulty::Rating { min: 0.0, max: Technique::NakedSingle.rating() }
+    }
+
+    /// Puzzles needing locked candidates, naked and hidden pairs, naked triples, X-Wings (finned or
+    /// not), or Swordfish, but no larger subsets, wings, or chains.
+    pub fn medium() -> Difficulty {
+        Difficulty::Rating { min: Technique::LockedCandidatesPointing.rating(), max: Technique::Swordfish.rating() }
+    }
+
+    /// Puzzles needing harder techniques than `Difficulty::medium`.
+    pub fn hard() -> Difficulty {
+        Difficulty::Rating { min: Technique::HiddenTriple.rating(), max: f32::INFINITY }
+    }
+
+    /// Whether the puzzle falls within the band. Puzzles which can't be rated never do.
+    pub fn matches(&self, board: &Board) -> bool {
+        match *self {
+            Difficulty::Rating { min, max } => match Rater::new().rate(board) {
+                Ok(rating) => min <= rating.score && rating.score <= max,
+                Err(_) => false
+            },
+            Difficulty::Techniques { ref allowed, ref required } => {
+                if board.check_givens().is_err() {
+                    return false;
+                }
+                let techniques: Vec<Technique> = Technique::all().into_iter().filter(|technique| allowed.contains(technique)).collect();
+                let mut candidates = Candidates::from_board(board);
+                let steps = LogicalSolver::with_techniques(&techniques).solve_candidates(&mut candidates);
+                candidates.is_complete()
+                    && required.iter().all(|&technique| steps.iter().any(|step| step.technique == technique))
+            }
+        }
+    }
+}
+
+/// Rates puzzles by solving them with the `LogicalSolver`, always using the simplest technique
+/// that makes progress, and scoring them by the hardest technique that was needed. Unlike solve
+/// times, this tracks how hard a puzzle feels to a person.
+pub struct Rater {
+    solver: LogicalSolver,
+    // Whether the solver uses techniques that are only valid for puzzles with a unique solution.
+    assumes_uniqueness: bool
+}
+
+impl Rater {
+    /// Creates a rater that only uses techniques which work whether or not the puzzle has a unique
+    /// solution.
+    pub fn new() -> Rater {
+        Rater {
+            solver: LogicalSolver::new(),
+            assumes_uniqueness: false
+        }
+    }
+
+    /// Creates a rater that also uses techniques assuming a unique solution, which makes some
+    /// puzzles rate lower. Ambiguous puzzles fail to rate with `SolveError::MultipleSolutions`.
+    pub fn with_uniqueness_techniques() -> Rater {
+        Rater {
+            solver: LogicalSolver::with_uniqueness_techniques(),
+            assumes_uniqueness: true
+        }
+    }
+
+    /// Rates the puzzle. Fails with `SolveError::Aborted` if it needs techniques beyond the
+    /// solver's repertoire.
+    pub fn rate(&self, board: &Board) -> Result<Rating, SolveError> {
+        board.check_givens()?;
+        if self.assumes_uniqueness {
+            match board.count_solutions(2) {
+                0 => return Err(SolveError::NoSolution),
+                1 => (),
+                _ => return Err(SolveError::MultipleSolutions)
+            }
+        }
+
+        let mut candidates = Candidates::from_board(board);
+        let steps = self.solver.solve_candidates(&mut candidates);
+        if !candidates.is_complete() {
+            return Err(if candidates.has_empty_cell() { SolveError::NoSolution } else { SolveError::Aborted });
+        }
+
+        let hardest = steps.iter()
+            .map(|step| step.technique)
+            .fold(None, |hardest: Option<Technique>, technique| match hardest {
+                Some(hardest) if hardest.rating() >= technique.rating() => Some(hardest),
+                _ => Some(technique)
+            });
+        Ok(Rating {
+            score: hardest.map_or(0.0, Technique::rating),
+            hardest,
+            steps: steps.len()
+        })
+    }
+}
+
+impl Default for Rater {
+    fn default() -> Rater {
+        Rater::new()
+    }
+}
+
+impl Display for Rating {
+    // Writes the score with one decimal place, followed by the hardest technique, eg 4.2 (XY-Wing).
+    fn fmt(&self, f: &mut Formatter) -> Result<(), ::std::fmt::Error> {
+        write!(f, "{:.1}", self.score)?;
+        if let Some(hardest) = self.hardest {
+            write!(f, " ({})", hardest)?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use board::Board;
+    use logic::Technique;
+    use SolveError;
+    use super::{Difficulty, Rater};
+
+    fn rate(puzzle: &str) -> Result<super::Rating, SolveError> {
+        Rater::new().rate(&Board::from_singleline_str(puzzle).unwrap())
+    }
+
+    #[test]
+    fn rates_by_hardest_technique() {
+        let easy = rate("530070000600195000098000060800060003400803001700020006060000280000419005000080079").unwrap();
+        let harder = rate("000400002150300080000002009900040000700001800000050090000000200007680000609070010").unwrap();
+        assert!(easy.score < harder.score);
+        assert_eq!(Some(easy.score), easy.hardest.map(Technique::rating));
+        assert_eq!(Some(harder.score), harder.hardest.map(Technique::rating));
+        assert!(easy.steps > 0);
+    }
+
+    #[test]
+    fn complete_board_rates_zero() {
+        let rating = rate("534678912672195348198342567859761423426853791713924856961537284287419635345286179").unwrap();
+        assert_eq!(0.0, rating.score);
+        assert_eq!(None, rating.hardest);
+        assert_eq!("0.0", rating.to_string());
+    }
+
+    #[test]
+    fn failures() {
+        assert_eq!(Err(SolveError::Aborted), rate("800000000003600000070090200050007000000045700000100030001000068008500010090000400"));
+        let ambiguous = Board::from_singleline_str("534678912672195300198342567859761423426853791713924856961537200287419635345286179").unwrap();
+        assert_eq!(Err(SolveError::MultipleSolutions), Rater::with_uniqueness_techniques().rate(&ambiguous));
+    }
+
+    #[test]
+    fn display() {
+        let rating = rate("530070000600195000098000060800060003400803001700020006060000280000419005000080079").unwrap();
+        assert_eq!(format!("{:.1} ({})", rating.score, rating.hardest.unwrap()), rating.to_string());
+    }
+
+    #[test]
+    fn difficulty_bands() {
+        let easy = Board::from_singleline_str("530070000600195000098000060800060003400803001700020006060000280000419005000080079").unwrap();
+        let harder = Board::from_singleline_str("000400002150300080000002009900040000700001800000050090000000200007680000609070010").unwrap();
+        assert!(Difficulty::easy().matches(&easy));
+        assert!(!Difficulty::medium().matches(&easy));
+        assert!(!Difficulty::easy().matches(&harder));
+        assert!(Difficulty::hard().matches(&harder));
+
+        let singles = Difficulty::Techniques {
+            allowed: vec![Technique::NakedSingle, Technique::HiddenSingle],
+            required: vec![Technique::HiddenSingle]
+        };
+        assert!(singles.matches(&easy));
+        assert!(!singles.matches(&harder));
+        let needs_pairs = Difficulty::Techniques {
+            allowed: vec![Technique::NakedSingle, Technique::HiddenSingle, Technique::NakedPair],
+            required: vec![Technique::NakedPair]
+        };
+        assert!(!needs_pairs.matches(&easy));
+    }
+}
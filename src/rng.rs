@@ -0,0 +1,63 @@
+/// A small seeded pseudo-random number generator (SplitMix64). It is implemented here rather than
+/// taken from the platform so that a seed produces the same numbers, and so the same puzzles, on
+/// every machine and with every version of Rust.
+#[derive(Debug, Clone)]
+pub(crate) struct Rng {
+    state: u64
+}
+
+impl Rng {
+    pub fn new(seed: u64) -> Rng {
+        Rng { state: seed }
+    }
+
+    pub fn next_u64(&mut self) -> u64 {
+        self.state = self.state.wrapping_add(0x9E37_79B9_7F4A_7C15);
+        let mut z = self.state;
+        z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+        z ^ (z >> 31)
+    }
+
+    /// A number in [0, bound). `bound` must not be 0.
+    pub fn below(&mut self, bound: usize) -> usize {
+        assert!(bound > 0);
+        // Scaling the full 64 bits down keeps the bias negligible for the small bounds used here.
+        ((self.next_u64() as u128 * bound as u128) >> 64) as usize
+    }
+
+    /// Shuffles the items into a uniformly random order (Fisher-Yates).
+    pub fn shuffle<T>(&mut self, items: &mut [T]) {
+        for i in (1..items.len()).rev() {
+            let j = self.below(i + 1);
+            items.swap(i, j);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Rng;
+
+    #[test]
+    fn same_seed_same_numbers() {
+        let mut first = Rng::new(7);
+        let mut second = Rng::new(7);
+        for _ in 0..100 {
+            assert_eq!(first.next_u64(), second.next_u64());
+        }
+        // The reference SplitMix64 output for seed 0, so the sequence can't drift between builds.
+        assert_eq!(0xE220_A839_7B1D_CDAF, Rng::new(0).next_u64());
+    }
+
+    #[test]
+    fn shuffle_permutes() {
+        let mut rng = Rng::new(1);
+        let mut items: Vec<usize> = (0..20).collect();
+        rng.shuffle(&mut items);
+        assert_ne!((0..20).collect::<Vec<_>>(), items);
+        items.sort();
+        assert_eq!((0..20).collect::<Vec<_>>(), items);
+        assert!((0..1000).all(|_| rng.below(9) < 9));
+    }
+}
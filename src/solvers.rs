@@ -0,0 +1,360 @@
+use board::{box_of, fill_matrix, Board, Possibility};
+use candidates::CandidateSet;
+use matrix::Matrix;
+use rng::Rng;
+use {Solve, SolveError};
+
+/// Solves boards with dancing links over the generic exact cover `Matrix`. The full Sudoku
+/// matrix is built once when the solver is created. Each solve only covers the columns of the
+/// board's givens and uncovers them again afterwards, so a single solver can be reused cheaply
+/// for any number of boards.
+pub struct MatrixSolver {
+    // Always in its pristine, fully uncovered state between calls.
+    matrix: Matrix
+}
+
+impl MatrixSolver {
+    pub fn new() -> MatrixSolver {
+        let mut matrix = Matrix::new(9 * 9 * 9, 9 * 9 * 4, 9 * 9 * 9 * 4);
+        fill_matrix(&mut matrix);
+        MatrixSolver { matrix }
+    }
+
+    /// Finds every completion of the board. See `Board::solve_all`.
+    pub fn solve_all(&mut self, board: &Board) -> Vec<Board> {
+        let mut solutions = Vec::new();
+        if board.check_givens().is_err() {
+            return solutions;
+        }
+
+        board.cover_givens(&mut self.matrix);
+        self.matrix.for_each_solution(|solution_rows| {
+            let mut solution = board.clone();
+            solution.apply_solution_rows(solution_rows);
+            solutions.push(solution);
+        });
+        board.uncover_givens(&mut self.matrix);
+
+        solutions
+    }
+
+    /// Counts the completions of the board, up to `limit`. See `Board::count_solutions`.
+    pub fn count_solutions(&mut self, board: &Board, limit: usize) -> usize {
+        if board.check_givens().is_err() {
+            return 0;
+        }
+
+        board.cover_givens(&mut self.matrix);
+        let count = self.matrix.count_solutions(limit);
+        board.uncover_givens(&mut self.matrix);
+
+        count
+    }
+
+    /// Whether the board is a minimal puzzle. See `Board::is_minimal`.
+    pub fn is_minimal(&mut self, board: &Board) -> bool {
+        if self.count_solutions(board, 2) != 1 {
+            return false;
+        }
+
+        let mut reduced = board.clone();
+        for (row, column, entry) in board.cells() {
+            if let Some(number) = entry {
+                reduced.clear_entry(row, column);
+                let unique = self.count_solutions(&reduced, 2) == 1;
+                reduced.set_entry(row, column, number);
+                if unique {
+                    return false;
+                }
+            }
+        }
+        true
+    }
+
+    // Fills the board in place with a random one of its completions, chosen by `rng`.
+    pub(crate) fn solve_random(&mut self, board: &mut Board, rng: &mut Rng) -> Result<(), SolveError> {
+        board.check_givens()?;
+
+        board.cover_givens(&mut self.matrix);
+        let result = self.matrix.solve_random(rng);
+        board.uncover_givens(&mut self.matrix);
+
+        board.apply_solution_rows(&result?);
+        Ok(())
+    }
+
+    /// Solves the board in place, failing if it has more than one completion. See `Board::solve_unique`.
+    pub fn solve_unique(&mut self, board: &mut Board) -> Result<(), SolveError> {
+        board.check_givens()?;
+
+        // Counting up to two solutions also finds the first, so uniqueness costs no extra search.
+        board.cover_givens(&mut self.matrix);
+        let result = self.matrix.solve_unique();
+        board.uncover_givens(&mut self.matrix);
+
+        board.apply_solution_rows(&result?);
+        Ok(())
+    }
+}
+
+impl Default for MatrixSolver {
+    fn default() -> MatrixSolver {
+        MatrixSolver::new()
+    }
+}
+
+impl Solve for MatrixSolver {
+    fn solve(&mut self, board: &mut Board) -> Result<(), SolveError> {
+        board.check_givens()?;
+
+        // Remove entries corresponding to knowns.
+        board.cover_givens(&mut self.matrix);
+        // Note that these solution_rows do not include the givens, but that's ok because
+        // the board already has the givens filled in.
+        let solution_rows = self.matrix.solve();
+        board.uncover_givens(&mut self.matrix);
+
+        board.apply_solution_rows(&solution_rows?);
+
+        Ok(())
+    }
+}
+
+/// Solves boards with a straightforward depth-first search over cell values, without any
+/// exact cover machinery. Mostly useful as a baseline to compare the other solvers against.
+/// Because plain backtracking can take a very long time on adversarial puzzles, the search can
+/// optionally be capped at a number of placements, after which it fails with `SolveError::Aborted`.
+pub struct BacktrackingSolver {
+    // The maximum number of placements a single solve may try, if any.
+    step_limit: Option<usize>,
+    // Placements tried so far by the current solve.
+    steps: usize,
+    // Bit n is set if number n is already used in the row, column, or box.
+    rows: [u16; 9],
+    columns: [u16; 9],
+    boxes: [u16; 9],
+    // Row-major cell values, 0 for unknown.
+    entries: [usize; 9 * 9],
+    // Matrix rows of the numbers placed by the search, of which the first solution_len are valid.
+    solution: [usize; 9 * 9],
+    solution_len: usize
+}
+
+impl BacktrackingSolver {
+    pub fn new() -> BacktrackingSolver {
+        BacktrackingSolver {
+            step_limit: None,
+            steps: 0,
+            rows: [0; 9],
+            columns: [0; 9],
+            boxes: [0; 9],
+            entries: [0; 9 * 9],
+            solution: [0; 9 * 9],
+            solution_len: 0
+        }
+    }
+
+    /// Creates a solver which aborts any solve that tries more than `step_limit` placements.
+    pub fn with_step_limit(step_limit: usize) -> BacktrackingSolver {
+        BacktrackingSolver {
+            step_limit: Some(step_limit),
+            ..BacktrackingSolver::new()
+        }
+    }
+
+    fn clear(&mut self) {
+        self.steps = 0;
+        self.rows = [0; 9];
+        self.columns = [0; 9];
+        self.boxes = [0; 9];
+        self.entries = [0; 9 * 9];
+        self.solution_len = 0;
+    }
+
+    // Returns a mask of the numbers that can still be placed in a cell.
+    fn allowed(&self, row: usize, column: usize) -> u16 {
+        let used = self.rows[row] | self.columns[column] | self.boxes[box_of(row, column)];
+        !used & CandidateSet::ALL_BITS
+    }
+
+    fn place(&mut self, row: usize, column: usize, number: usize) {
+        let bit = 1 << number;
+        self.rows[row] |= bit;
+        self.columns[column] |= bit;
+        self.boxes[box_of(row, column)] |= bit;
+        self.entries[column + row * 9] = number;
+    }
+
+    fn unplace(&mut self, row: usize, column: usize, number: usize) {
+        let bit = !(1 << number);
+        self.rows[row] &= bit;
+        self.columns[column] &= bit;
+        self.boxes[box_of(row, column)] &= bit;
+        self.entries[column + row * 9] = 0;
+    }
+
+    // Returns Ok(true) if every cell could be filled, in which case the first solution_len entries of
+    // solution hold the placed numbers' matrix rows.
+    fn search(&mut self) -> Result<bool, SolveError> {
+        // Branch on the unknown cell with the fewest allowed numbers, so that dead ends are found early.
+        let mut best: Option<(usize, usize, u16)> = None;
+        for row in 0..9 {
+            for column in 0..9 {
+                if self.entries[column + row * 9] != 0 {
+                    continue;
+                }
+                let allowed = self.allowed(row, column);
+                let is_better = match best {
+                    Some((_, _, best_allowed)) => allowed.count_ones() < best_allowed.count_ones(),
+                    None => true
+                };
+                if is_better {
+                    best = Some((row, column, allowed));
+                }
+            }
+        }
+
+        let (row, column, allowed) = match best {
+            Some(best) => best,
+            // No unknown cells remain, so the board is solved.
+            None => return Ok(true)
+        };
+
+        for number in 1..10 {
+            if allowed & (1 << number) == 0 {
+                continue;
+            }
+
+            self.steps += 1;
+            if let Some(step_limit) = self.step_limit {
+                if self.steps > step_limit {
+                    return Err(SolveError::Aborted);
+                }
+            }
+
+            self.place(row, column, number);
+            self.solution[self.solution_len] = Possibility::new(row, column, number).get_matrix_row();
+            self.solution_len += 1;
+
+            if self.search()? {
+                return Ok(true);
+            }
+
+            self.solution_len -= 1;
+            self.unplace(row, column, number);
+        }
+
+        Ok(false)
+    }
+}
+
+impl Default for BacktrackingSolver {
+    fn default() -> BacktrackingSolver {
+        BacktrackingSolver::new()
+    }
+}
+
+impl Solve for BacktrackingSolver {
+    fn solve(&mut self, board: &mut Board) -> Result<(), SolveError> {
+        board.check_givens()?;
+        self.clear();
+
+        for row in 0..9 {
+            for column in 0..9 {
+                if let Some(number) = board.get_entry(row, column) {
+                    self.place(row, column, number);
+                }
+            }
+        }
+
+        if !self.search()? {
+            return Err(SolveError::NoSolution);
+        }
+        board.apply_solution_rows(&self.solution[..self.solution_len]);
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use board::{Board, Conflict, Constraint};
+    use dlx::DLXSolver;
+    use {Solve, SolveError};
+    use super::{BacktrackingSolver, MatrixSolver};
+
+    const PUZZLES: [&str; 3] = [
+        "530070000600195000098000060800060003400803001700020006060000280000419005000080079",
+        "000000010400000000020000000000050407008000300001090000300400200050100000000806000",
+        "800000000003600000070090200050007000000045700000100030001000068008500010090000400"
+    ];
+
+    // The conflicts in a board whose only givens are 1s in its top left two cells.
+    fn repeated_ones() -> Vec<Conflict> {
+        vec![
+            Conflict { first: (0, 0), second: (0, 1), number: 1, constraint: Constraint::Row },
+            Conflict { first: (0, 0), second: (0, 1), number: 1, constraint: Constraint::Box }
+        ]
+    }
+
+    #[test]
+    fn solvers_agree() {
+        let mut solvers: Vec<Box<dyn Solve>> = vec![
+            Box::new(MatrixSolver::new()),
+            Box::new(DLXSolver::new()),
+            Box::new(BacktrackingSolver::new())
+        ];
+        for puzzle in PUZZLES.iter() {
+            let mut expected = Board::from_singleline_str(puzzle).unwrap();
+            expected.solve().unwrap();
+            for solver in solvers.iter_mut() {
+                let mut actual = Board::from_singleline_str(puzzle).unwrap();
+                actual.solve_with(&mut **solver).unwrap();
+                assert_eq!(expected, actual);
+            }
+        }
+    }
+
+    #[test]
+    fn matrix_solver_is_reusable() {
+        let mut solver = MatrixSolver::new();
+        let pristine = solver.matrix.clone();
+        for puzzle in PUZZLES.iter() {
+            let board = Board::from_singleline_str(puzzle).unwrap();
+
+            let mut solved = board.clone();
+            solver.solve(&mut solved).unwrap();
+            assert!(pristine == solver.matrix);
+
+            assert_eq!(1, solver.count_solutions(&board, 2));
+            assert!(pristine == solver.matrix);
+
+            assert_eq!(vec![solved], solver.solve_all(&board));
+            assert!(pristine == solver.matrix);
+        }
+    }
+
+    #[test]
+    fn backtracking_rejects_repeated_givens() {
+        let mut board = Board::from_singleline_str("110000000000000000000000000000000000000000000000000000000000000000000000000000000").unwrap();
+        assert_eq!(Err(SolveError::ContradictoryGivens(repeated_ones())), board.solve_with(&mut BacktrackingSolver::new()));
+    }
+
+    #[test]
+    fn backtracking_step_limit() {
+        let mut board = Board::from_singleline_str(PUZZLES[2]).unwrap();
+        assert_eq!(Err(SolveError::Aborted), board.clone().solve_with(&mut BacktrackingSolver::with_step_limit(10)));
+        assert_eq!(Ok(()), board.solve_with(&mut BacktrackingSolver::with_step_limit(1_000_000)));
+    }
+
+    #[test]
+    fn matrix_solver_ignores_contradictory_givens() {
+        let mut solver = MatrixSolver::new();
+        let pristine = solver.matrix.clone();
+        let mut board = Board::from_singleline_str("110000000000000000000000000000000000000000000000000000000000000000000000000000000").unwrap();
+        assert_eq!(Err(SolveError::ContradictoryGivens(repeated_ones())), solver.solve(&mut board));
+        assert_eq!(0, solver.count_solutions(&board, 2));
+        assert!(solver.solve_all(&board).is_empty());
+        assert!(pristine == solver.matrix);
+    }
+}
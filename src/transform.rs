@@ -0,0 +1,293 @@
+use board::Board;
+use rng::Rng;
+
+/// A transformation which turns any valid board into another valid board: transposing, reordering
+/// the bands, the stacks, and the rows and columns within them, and relabeling the numbers.
+/// Rotations and reflections are combinations of these. Transformations are composed with `then`
+/// and applied with `Board::transform`. A puzzle keeps its number of solutions and its difficulty.
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub struct Transformation {
+    // Whether the board is transposed before its rows and columns are reordered.
+    transposed: bool,
+    // The row, after transposing, which each row of the result comes from.
+    rows: [usize; 9],
+    // The column, after transposing, which each column of the result comes from.
+    columns: [usize; 9],
+    // The number each number becomes, indexed by number. Index 0 is unused.
+    numbers: [usize; 10]
+}
+
+const IDENTITY: [usize; 9] = [0, 1, 2, 3, 4, 5, 6, 7, 8];
+const REVERSED: [usize; 9] = [8, 7, 6, 5, 4, 3, 2, 1, 0];
+
+impl Transformation {
+    /// The transformation which leaves boards unchanged.
+    pub fn identity() -> Transformation {
+        Transformation {
+            transposed: false,
+            rows: IDENTITY,
+            columns: IDENTITY,
+            numbers: [0, 1, 2, 3, 4, 5, 6, 7, 8, 9]
+        }
+    }
+
+    /// Swaps the rows with the columns, mirroring the board across the diagonal from the top left
+    /// to the bottom right.
+    pub fn transpose() -> Transformation {
+        Transformation { transposed: true, ..Transformation::identity() }
+    }
+
+    /// Turns the board a quarter of the way around, clockwise.
+    pub fn rotate_clockwise() -> Transformation {
+        Transformation { transposed: true, columns: REVERSED, ..Transformation::identity() }
+    }
+
+    /// Turns the board a quarter of the way around, counterclockwise.
+    pub fn rotate_counterclockwise() -> Transformation {
+        Transformation { transposed: true, rows: REVERSED, ..Transformation::identity() }
+    }
+
+    /// Turns the board half way around.
+    pub fn rotate_half() -> Transformation {
+        Transformation { rows: REVERSED, columns: REVERSED, ..Transformation::identity() }
+    }
+
+    /// Mirrors the board across its middle row, swapping the top and bottom.
+    pub fn reflect_horizontal() -> Transformation {
+        Transformation { rows: REVERSED, ..Transformation::identity() }
+    }
+
+    /// Mirrors the board across its middle column, swapping the left and right.
+    pub fn reflect_vertical() -> Transformation {
+        Transformation { columns: REVERSED, ..Transformation::identity() }
+    }
+
+    /// Reorders the bands, so that band i of the result is band `order[i]` of the board. `order`
+    /// must contain each of 0, 1, and 2 once.
+    pub fn permute_bands(order: [usize; 3]) -> Transformation {
+        Transformation { rows: permute_groups(order), ..Transformation::identity() }
+    }
+
+    /// Reorders the stacks, so that stack i of the result is stack `order[i]` of the board.
+    /// `order` must contain each of 0, 1, and 2 once.
+    pub fn permute_stacks(order: [usize; 3]) -> Transformation {
+        Transformation { columns: permute_groups(order), ..Transformation::identity() }
+    }
+
+    /// Reorders the rows within a band, so that row i of the band in the result is row `order[i]`
+    /// of the band in the board. `order` must contain each of 0, 1, and 2 once.
+    pub fn permute_rows(band: usize, order: [usize; 3]) -> Transformation {
+        Transformation { rows: permute_within_group(band, order), ..Transformation::identity() }
+    }
+
+    /// Reorders the columns within a stack, so that column i of the stack in the result is column
+    /// `order[i]` of the stack in the board. `order` must contain each of 0, 1, and 2 once.
+    pub fn permute_columns(stack: usize, order: [usize; 3]) -> Transformation {
+        Transformation { columns: permute_within_group(stack, order), ..Transformation::identity() }
+    }
+
+    /// Relabels the numbers, so that each number n becomes `labels[n - 1]`. `labels` must contain
+    /// each of the numbers 1 to 9 once.
+    pub fn relabel(labels: [usize; 9]) -> Transformation {
+        let mut sorted = labels;
+        sorted.sort();
+        assert_eq!([1, 2, 3, 4, 5, 6, 7, 8, 9], sorted);
+        let mut numbers = [0; 10];
+        numbers[1..].copy_from_slice(&labels);
+        Transformation { numbers, ..Transformation::identity() }
+    }
+
+    /// Chooses a transformation uniformly at random from all 3,359,232 × 9! of them. The same seed
+    /// always gives the same transformation.
+    pub fn random(seed: u64) -> Transformation {
+        let mut rng = Rng::new(seed);
+        let mut order = || {
+            let mut order = [0, 1, 2];
+            rng.shuffle(&mut order);
+            order
+        };
+        let mut transformation = Transformation::permute_bands(order())
+            .then(&Transformation::permute_stacks(order()));
+        for group in 0..3 {
+            transformation = transformation
+                .then(&Transformation::permute_rows(group, order()))
+                .then(&Transformation::permute_columns(group, order()));
+        }
+        let mut labels = [1, 2, 3, 4, 5, 6, 7, 8, 9];
+        rng.shuffle(&mut labels);
+        transformation = transformation.then(&Transformation::relabel(labels));
+        if rng.below(2) == 1 {
+            transformation = transformation.then(&Transformation::transpose());
+        }
+        transformation
+    }
+
+    /// The transformation which applies this one and then the other.
+    pub fn then(&self, other: &Transformation) -> Transformation {
+        // Transposing after reordering is the same as reordering the other way around after
+        // transposing.
+        let (rows, columns) = if other.transposed { (self.columns, self.rows) } else { (self.rows, self.columns) };
+        let mut composed = Transformation {
+            transposed: self.transposed != other.transposed,
+            ..Transformation::identity()
+        };
+        for i in 0..9 {
+            composed.rows[i] = rows[other.rows[i]];
+            composed.columns[i] = columns[other.columns[i]];
+        }
+        for number in 1..10 {
+            composed.numbers[number] = other.numbers[self.numbers[number]];
+        }
+        composed
+    }
+
+    /// The transformation which undoes this one.
+    pub fn inverse(&self) -> Transformation {
+        let mut inverse = Transformation::identity();
+        for i in 0..9 {
+            inverse.rows[self.rows[i]] = i;
+            inverse.columns[self.columns[i]] = i;
+        }
+        for number in 1..10 {
+            inverse.numbers[self.numbers[number]] = number;
+        }
+        // The inverse reorders first and then transposes, which `then` can express.
+        if self.transposed {
+            Transformation { rows: inverse.rows, columns: inverse.columns, ..Transformation::identity() }
+                .then(&Transformation { numbers: inverse.numbers, ..Transformation::transpose() })
+        } else {
+            inverse
+        }
+    }
+}
+
+impl Default for Transformation {
+    fn default() -> Transformation {
+        Transformation::identity()
+    }
+}
+
+impl Board {
+    /// Applies the transformation to the board, returning the transformed board.
+    pub fn transform(&self, transformation: &Transformation) -> Board {
+        let mut transformed = Board::new();
+        for row in 0..9 {
+            for column in 0..9 {
+                let (from_row, from_column) = (transformation.rows[row], transformation.columns[column]);
+                let (from_row, from_column) = if transformation.transposed { (from_column, from_row) } else { (from_row, from_column) };
+                if let Some(number) = self.get_entry(from_row, from_column) {
+                    transformed.set_entry(row, column, transformation.numbers[number]);
+                }
+            }
+        }
+        transformed
+    }
+
+    /// Applies a random transformation chosen from the seed, see `Transformation::random`. The
+    /// result looks like a different puzzle, but is equivalent to this one and just as hard.
+    pub fn random_equivalent(&self, seed: u64) -> Board {
+        self.transform(&Transformation::random(seed))
+    }
+}
+
+// Rows or columns in the order that puts group `order[i]` of three in place of group i.
+fn permute_groups(order: [usize; 3]) -> [usize; 9] {
+    check_order(order);
+    let mut permuted = IDENTITY;
+    for (group, &from) in order.iter().enumerate() {
+        for i in 0..3 {
+            permuted[group * 3 + i] = from * 3 + i;
+        }
+    }
+    permuted
+}
+
+// Rows or columns in the order that reorders those in one group of three.
+fn permute_within_group(group: usize, order: [usize; 3]) -> [usize; 9] {
+    assert!(group < 3);
+    check_order(order);
+    let mut permuted = IDENTITY;
+    for (i, &from) in order.iter().enumerate() {
+        permuted[group * 3 + i] = group * 3 + from;
+    }
+    permuted
+}
+
+fn check_order(order: [usize; 3]) {
+    let mut sorted = order;
+    sorted.sort();
+    assert_eq!([0, 1, 2], sorted);
+}
+
+#[cfg(test)]
+mod tests {
+    use board::Board;
+    use super::Transformation;
+
+    const EASY: &str = "530070000600195000098000060800060003400803001700020006060000280000419005000080079";
+
+    #[test]
+    fn rotations() {
+        let board = Board::from_singleline_str(EASY).unwrap();
+        let rotated = board.transform(&Transformation::rotate_clockwise());
+        // The top left corner moves to the top right.
+        assert_eq!(Some(5), rotated.get_entry(0, 8));
+        assert_eq!(Some(3), rotated.get_entry(1, 8));
+        assert_eq!(board.transform(&Transformation::rotate_half()), rotated.transform(&Transformation::rotate_clockwise()));
+        assert_eq!(board, rotated.transform(&Transformation::rotate_counterclockwise()));
+
+        let four_turns = (0..4).fold(Transformation::identity(), |turns, _| turns.then(&Transformation::rotate_clockwise()));
+        assert_eq!(Transformation::identity(), four_turns);
+    }
+
+    #[test]
+    fn reflections_and_permutations() {
+        let board = Board::from_singleline_str(EASY).unwrap();
+        let reflected = board.transform(&Transformation::reflect_horizontal());
+        assert_eq!(board.get_entry(0, 1), reflected.get_entry(8, 1));
+        let reflected = board.transform(&Transformation::reflect_vertical());
+        assert_eq!(board.get_entry(0, 1), reflected.get_entry(0, 7));
+        let transposed = board.transform(&Transformation::transpose());
+        assert_eq!(board.get_entry(0, 1), transposed.get_entry(1, 0));
+
+        let bands = board.transform(&Transformation::permute_bands([2, 0, 1]));
+        assert_eq!(board.get_entry(6, 1), bands.get_entry(0, 1));
+        let stacks = board.transform(&Transformation::permute_stacks([2, 0, 1]));
+        assert_eq!(board.get_entry(1, 6), stacks.get_entry(1, 0));
+        let rows = board.transform(&Transformation::permute_rows(1, [1, 2, 0]));
+        assert_eq!(board.get_entry(4, 0), rows.get_entry(3, 0));
+        let columns = board.transform(&Transformation::permute_columns(1, [1, 2, 0]));
+        assert_eq!(board.get_entry(0, 4), columns.get_entry(0, 3));
+        let relabeled = board.transform(&Transformation::relabel([9, 8, 7, 6, 5, 4, 3, 2, 1]));
+        assert_eq!(Some(5), relabeled.get_entry(0, 0));
+        assert_eq!(Some(7), relabeled.get_entry(0, 1));
+    }
+
+    #[test]
+    fn composition() {
+        let board = Board::from_singleline_str(EASY).unwrap();
+        let first = Transformation::random(1);
+        let second = Transformation::random(2);
+        assert_eq!(board.transform(&first).transform(&second), board.transform(&first.then(&second)));
+        assert_eq!(board, board.transform(&first).transform(&first.inverse()));
+        assert_eq!(Transformation::identity(), second.then(&second.inverse()));
+    }
+
+    #[test]
+    #[should_panic]
+    fn relabel_needs_every_number() {
+        Transformation::relabel([1, 1, 3, 4, 5, 6, 7, 8, 9]);
+    }
+
+    #[test]
+    fn random_equivalent() {
+        let board = Board::from_singleline_str(EASY).unwrap();
+        let equivalent = board.random_equivalent(3);
+        assert_eq!(equivalent, board.random_equivalent(3));
+        assert_ne!(board, equivalent);
+        assert!(board.is_equivalent(&equivalent));
+        assert!(equivalent.has_unique_solution());
+        assert!(equivalent.validate().is_empty());
+        assert_eq!(board.rate().unwrap().score, equivalent.rate().unwrap().score);
+    }
+}